@@ -1,37 +1,75 @@
 use std::time::Duration;
 
-use red_sdl::ui_element::grid::{Grid, Pos};
 use sdl2::rect::FRect;
 
 use crate::{
-    map_plant::MapPlant,
-    plants::nenuphar::Nenuphar,
-    projectile::{DamageType, Projectile},
-    zombie::Zombie,
-    State,
+    plants::{tile::Tile, AreaDamage},
+    projectile::Projectile,
+    zombie::{reachable, Hit, HitSource, Zombie},
 };
 
-use super::{
-    config::{Map, RowType},
-    Level,
-};
+use super::{config::Map, simulation::Simulation};
+
+impl Simulation {
+    pub(super) fn detonate_plants(&mut self) {
+        let mut detonated = Vec::new();
+        for (y, row) in self.plants.iter_mut().enumerate() {
+            for (x, tile) in row.iter_mut().enumerate() {
+                for (layer, slot) in tile.layers.iter_mut().enumerate() {
+                    if let Some(plant) = slot.as_mut() {
+                        let area = plant.detonate(
+                            self.map.pos_to_coord_x(x) + self.map.col_width() / 2.,
+                            y,
+                            self.map.col_width(),
+                        );
+                        if let Some(area) = area {
+                            detonated.push((x, y, layer, area));
+                        }
+                    }
+                }
+            }
+        }
+        for (x, y, layer, area) in detonated {
+            do_area_damage(&mut self.zombies, &area);
+            self.plants[y][x].layers[layer] = None;
+        }
+    }
+
+    pub(super) fn act_plants(&mut self) {
+        let left = |x| self.map.pos_to_coord_x(x);
+        for (y, row) in self.plants.iter_mut().enumerate() {
+            for (x, tile) in row.iter_mut().enumerate() {
+                for slot in tile.layers.iter_mut() {
+                    if slot.as_mut().is_some_and(|plant| {
+                        plant.act_on_tile(
+                            left(x),
+                            left(x) + self.map.col_width(),
+                            &mut self.zombies[y],
+                        )
+                    }) {
+                        *slot = None;
+                    }
+                }
+            }
+        }
+    }
 
-impl Level {
-    pub(super) fn spawn_projectiles(&'static mut self) {
-        for (&Pos { x, y }, mut slot) in self.map_plants.iter_mut() {
-            let x = (x - 2) / 3;
-            let y = (y - 2) / 3;
-            if let Some(plant) = slot.as_mut().plant.as_mut() {
-                let mut spawns = plant.should_spawn(
-                    self.map.pos_to_coord_x(x) + plant.rect(0., 0.).width() / 2.,
-                    self.map.pos_to_coord_y(y),
-                    y,
-                    self.map.rows.len() - 1,
-                    &self.zombies,
-                );
-                self.suns.append(&mut spawns.0);
-                for (y, proj) in spawns.1 {
-                    self.projectiles[y].push(proj);
+    pub(super) fn spawn_projectiles(&mut self) {
+        let max_y = self.map.rows.len() - 1;
+        for (y, row) in self.plants.iter_mut().enumerate() {
+            for (x, tile) in row.iter_mut().enumerate() {
+                for plant in tile.layers.iter_mut().flatten() {
+                    let mut spawns = plant.should_spawn(
+                        self.map.pos_to_coord_x(x) + plant.rect(0., 0.).width() / 2.,
+                        self.map.pos_to_coord_y(y),
+                        y,
+                        max_y,
+                        &self.zombies,
+                    );
+                    self.suns.append(&mut spawns.0);
+                    for (y, proj) in spawns.1 {
+                        self.projectiles[y].push(proj);
+                    }
                 }
             }
         }
@@ -40,44 +78,121 @@ impl Level {
 
 pub(super) fn do_damage_to_plant(
     zombie: &mut dyn Zombie,
-    y: usize,
-    plants: &mut Grid<Level, State, MapPlant>,
+    tiles: &mut [Tile],
     config: &Map,
-    row_type: RowType,
     prev_x: f32,
     elapsed: Duration,
 ) {
-    let y = y * 3 + 2;
+    zombie.set_eating(false);
+    if zombie.airborne() {
+        return;
+    }
     if let Some(x) = config.coord_to_pos_x(prev_x) {
-        let x = x * 3 + 2;
-        if let Some(slot) = plants.get_element_mut(x, y) {
-            if let Some(plant) = slot.plant.as_mut() {
+        let Some(tile) = tiles.get_mut(x) else {
+            return;
+        };
+        if tile.blocker().is_some() && vault_over(zombie, tile, config, x) {
+            return;
+        }
+        if let Some(slot) = tile.blocker() {
+            if let Some(plant) = slot.as_mut() {
                 zombie.set_x(prev_x);
-                let diff = elapsed.as_secs_f32() * if zombie.freezed() { 0.5 } else { 1. };
+                zombie.set_eating(true);
+                let diff = elapsed.as_secs_f32() * zombie.eat_rate();
                 if plant.health().as_secs_f32() < diff {
-                    slot.plant = if row_type == RowType::Water && !plant.is_nenuphar() {
-                        Some(Box::new(Nenuphar::new()))
-                    } else {
-                        None
-                    }
+                    *slot = None;
                 } else {
                     *plant.health() -= Duration::from_secs_f32(diff);
                 }
             }
         }
     } else if let Some(x) = config.coord_to_pos_x(zombie.rect(0.).left()) {
-        let x = x * 3 + 2;
-        if let Some(slot) = plants.get_element_mut(x, y) {
-            if let Some(plant) = slot.plant.as_mut() {
-                let rect = plant.rect(config.pos_to_coord_x(x), 0.);
-                if zombie.rect(0.).has_intersection(rect) {
-                    zombie.set_x(rect.x() + rect.width());
-                }
+        let Some(tile) = tiles.get(x) else {
+            return;
+        };
+        if let Some(plant) = tile.plants().find(|plant| plant.blocks_zombies()) {
+            let rect = plant.rect(config.pos_to_coord_x(x * 3 + 2), 0.);
+            if zombie.rect(0.).has_intersection(rect) && !vault_over(zombie, tile, config, x) {
+                zombie.set_x(rect.x() + rect.width());
             }
         }
     }
 }
 
+fn vault_over(zombie: &mut dyn Zombie, tile: &Tile, config: &Map, x: usize) -> bool {
+    if zombie.vault() && !tile.plants().any(|plant| plant.definition().tall) {
+        zombie.set_x(config.pos_to_coord_x(x) - zombie.rect(0.).width());
+        true
+    } else {
+        false
+    }
+}
+
+pub(super) fn do_area_damage(zombies: &mut [Vec<Box<dyn Zombie>>], area: &AreaDamage) {
+    for row in zombies
+        .iter_mut()
+        .take(area.rows.end() + 1)
+        .skip(*area.rows.start())
+    {
+        row.retain_mut(|zombie| {
+            let hit_box = zombie.hit_box(0.);
+            if hit_box.right() < area.left || hit_box.left() > area.right {
+                return true;
+            }
+            !zombie
+                .hit(Hit::new(
+                    area.damage_amount,
+                    area.damage_type,
+                    HitSource::Area,
+                ))
+                .0
+        });
+    }
+}
+
+fn overlaps(hit_box: FRect, rect: FRect) -> bool {
+    hit_box.left() < rect.right() && hit_box.right() > rect.left()
+}
+
+pub(super) fn do_landing_damage(
+    row: &mut Vec<Box<dyn Zombie>>,
+    proj: &dyn Projectile,
+    splash_radius: f32,
+    splash_damage: usize,
+) {
+    let rect = proj.rect(0.);
+    let x = rect.x() + rect.width() / 2.;
+    let distance = |zombie: &dyn Zombie| {
+        let hit_box = zombie.hit_box(0.);
+        (hit_box.left() - x).max(x - hit_box.right()).max(0.)
+    };
+    let target = row
+        .iter()
+        .enumerate()
+        .filter(|(_, zombie)| reachable(zombie.as_ref(), proj.reaches_air()))
+        .map(|(i, zombie)| (i, distance(zombie.as_ref())))
+        .filter(|&(_, distance)| distance <= splash_radius)
+        .min_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
+        .map(|(i, _)| i);
+    let mut i = 0;
+    row.retain_mut(|zombie| {
+        let damage_amount = if target == Some(i) {
+            proj.damage_amount()
+        } else if reachable(zombie.as_ref(), proj.reaches_air())
+            && distance(zombie.as_ref()) <= splash_radius
+        {
+            splash_damage
+        } else {
+            0
+        };
+        i += 1;
+        damage_amount == 0
+            || !zombie
+                .hit(Hit::new(damage_amount, proj.damage_type(), proj.source()))
+                .0
+    });
+}
+
 pub(super) fn do_damage_to_zombies(
     row: &mut [Box<dyn Zombie>],
     proj: &dyn Projectile,
@@ -87,7 +202,7 @@ pub(super) fn do_damage_to_zombies(
         .enumerate()
         .filter_map(|(i, zombie)| {
             let hit_box = zombie.hit_box(0.);
-            if hit_box.has_intersection(proj.rect(0.)) {
+            if reachable(zombie.as_ref(), proj.reaches_air()) && overlaps(hit_box, proj.rect(0.)) {
                 Some((i, hit_box))
             } else {
                 None
@@ -96,45 +211,50 @@ pub(super) fn do_damage_to_zombies(
         .collect::<Vec<(usize, FRect)>>();
     zombies.sort_by(|(_, pos1), (_, pos2)| pos1.left().total_cmp(&pos2.left()));
     if let Some(&(zombie_index, _)) = zombies.first() {
-        (
-            true,
-            hit_zombie(
-                row,
-                zombie_index,
-                proj.damage_amount(),
-                proj.damage_type(),
-                false,
-            ),
-        )
+        (true, hit_zombie(row, zombie_index, projectile_hit(proj)))
     } else {
         (false, Vec::new())
     }
 }
 
-pub(super) fn hit_zombie(
+pub(super) fn do_piercing_damage(
     row: &mut [Box<dyn Zombie>],
-    zombie_index: usize,
-    damage_amount: usize,
-    damage_type: DamageType,
-    propagated: bool,
+    proj: &mut dyn Projectile,
 ) -> Vec<usize> {
-    let hit = row[zombie_index].hit(damage_amount, damage_type, propagated);
+    let mut zombies = row
+        .iter()
+        .enumerate()
+        .filter(|(_, zombie)| reachable(zombie.as_ref(), proj.reaches_air()))
+        .map(|(i, zombie)| (i, zombie.hit_box(0.)))
+        .filter(|&(_, hit_box)| overlaps(hit_box, proj.rect(0.)))
+        .collect::<Vec<(usize, FRect)>>();
+    zombies.sort_by(|(_, pos1), (_, pos2)| pos1.left().total_cmp(&pos2.left()));
     let mut to_remove = Vec::new();
-    if hit.0 {
+    for (zombie_index, _) in zombies {
+        if proj.pierce(row[zombie_index].uid()) {
+            to_remove.extend(hit_zombie(row, zombie_index, projectile_hit(proj)));
+        }
+    }
+    to_remove
+}
+
+fn projectile_hit(proj: &dyn Projectile) -> Hit {
+    Hit::new(proj.damage_amount(), proj.damage_type(), proj.source())
+}
+
+pub(super) fn hit_zombie(row: &mut [Box<dyn Zombie>], zombie_index: usize, hit: Hit) -> Vec<usize> {
+    let result = row[zombie_index].hit(hit);
+    let mut to_remove = Vec::new();
+    if result.0 {
         to_remove.push(zombie_index)
     }
-    if hit.1 && !propagated {
-        to_remove.extend(propagate(row, zombie_index, damage_amount, damage_type));
+    if result.1 && !hit.propagated {
+        to_remove.extend(propagate(row, zombie_index, hit));
     }
     to_remove
 }
 
-pub(super) fn propagate(
-    row: &mut [Box<dyn Zombie>],
-    zombie_index: usize,
-    damage_amount: usize,
-    damage_type: DamageType,
-) -> Vec<usize> {
+pub(super) fn propagate(row: &mut [Box<dyn Zombie>], zombie_index: usize, hit: Hit) -> Vec<usize> {
     let size = {
         let oz = row[zombie_index].as_ref();
         oz.hit_box(0.)
@@ -153,13 +273,7 @@ pub(super) fn propagate(
         .collect::<Vec<usize>>()
     {
         if zombie_index != zombie_index2 {
-            to_remove.extend(hit_zombie(
-                row,
-                zombie_index2,
-                damage_amount,
-                damage_type,
-                true,
-            ));
+            to_remove.extend(hit_zombie(row, zombie_index2, hit.propagated()));
         }
     }
     to_remove
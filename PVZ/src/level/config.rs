@@ -1,21 +1,54 @@
 use anyhow::{anyhow, Result};
-use rand::Rng;
-use std::{
-    collections::HashMap,
-    fs,
-    io::{self},
-    time::Duration,
+use serde_json::{json, Value};
+use std::{fs, path::Path, time::Duration};
+
+use crate::{plants::registry::PlantRegistry, zombie::registry::ZombieRegistry};
+
+use super::{
+    parse::{f32_value, read_json, ByteReader, Field, LoadError},
+    simulation::Simulation,
+    Level,
 };
 
-use crate::zombie::zombie_from_id;
+pub(super) const FORMAT_VERSION: u64 = 1;
 
-use super::Level;
+const LEGACY_ZOMBIES: [&str; 2] = ["normal", "cone"];
+const LEGACY_PLANTS: [&str; 6] = [
+    "nenuphar",
+    "sunflower",
+    "peashooter",
+    "ice_peashooter",
+    "fire_peashooter",
+    "triple_peashooter",
+];
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum RowType {
     Grass,
     Water,
 }
+
+impl RowType {
+    const fn name(self) -> &'static str {
+        match self {
+            RowType::Grass => "grass",
+            RowType::Water => "water",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grass" => Some(RowType::Grass),
+            "water" => Some(RowType::Water),
+            _ => None,
+        }
+    }
+
+    pub fn load(json: &Field) -> Result<Self, LoadError> {
+        RowType::from_name(json.str()?).ok_or_else(|| json.invalid("a row type"))
+    }
+}
+
 pub struct Map {
     pub id: u8,
 
@@ -59,41 +92,46 @@ impl Map {
         self.width / self.cols as f32
     }
 
-    pub fn load(map: u8) -> Result<Self> {
-        let mut map_data = fs::read(format!("assets/maps/{map}.data")).map_err(|e| anyhow!(e))?;
-        let top = f32::from_le_bytes([
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-        ]);
-        let left = f32::from_le_bytes([
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-        ]);
-        let width = f32::from_le_bytes([
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-        ]);
-        let height = f32::from_le_bytes([
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-            map_data.remove(0),
-        ]);
-        let rows = map_data.remove(0);
-        let cols = map_data.remove(0);
+    pub fn load(map: u8) -> Result<Self, LoadError> {
+        let file = format!("assets/maps/{map}.json");
+        if !Path::new(&file).exists() {
+            return Self::load_legacy(map);
+        }
+        let json = read_json(&file)?;
+        let json = Field::root(&file, &json);
+        check_version(&json)?;
+        Ok(Self {
+            id: map,
+            top: json.get("top").f32()?,
+            left: json.get("left").f32()?,
+            width: json.get("width").f32()?,
+            height: json.get("height").f32()?,
+            rows: json
+                .get("rows")
+                .items()?
+                .iter()
+                .map(RowType::load)
+                .collect::<Result<Vec<RowType>, LoadError>>()?,
+            cols: json.get("cols").u8()?,
+        })
+    }
+
+    pub fn load_legacy(map: u8) -> Result<Self, LoadError> {
+        let mut map_data = ByteReader::open(format!("assets/maps/{map}.data"))?;
+        let top = f32::from_le_bytes(map_data.bytes("the map top")?);
+        let left = f32::from_le_bytes(map_data.bytes("the map left")?);
+        let width = f32::from_le_bytes(map_data.bytes("the map width")?);
+        let height = f32::from_le_bytes(map_data.bytes("the map height")?);
+        let rows = map_data.u8("the rows count")?;
+        let cols = map_data.u8("the columns count")?;
         let rows_types = (0..rows)
-            .map(|_| match map_data.remove(0) {
-                0 => RowType::Grass,
-                1 => RowType::Water,
-                _ => panic!("Not found row type"),
+            .map(|_| match map_data.u8("a row type")? {
+                0 => Ok(RowType::Grass),
+                1 => Ok(RowType::Water),
+                row => Err(map_data.invalid_byte(row, "a row type")),
             })
-            .collect();
+            .collect::<Result<Vec<RowType>, LoadError>>()?;
+        map_data.end()?;
 
         Ok(Self {
             id: map,
@@ -107,113 +145,342 @@ impl Map {
     }
 
     pub fn save(&self) -> Result<()> {
-        let mut map_data = Vec::with_capacity(32);
-        map_data.extend(self.top.to_le_bytes());
-        map_data.extend(self.left.to_le_bytes());
-        map_data.extend(self.width.to_le_bytes());
-        map_data.extend(self.height.to_le_bytes());
-        map_data.push(self.rows.len() as u8);
-        map_data.push(self.cols);
-        map_data.extend(self.rows.iter().map(|row| match row {
-            RowType::Grass => 0,
-            RowType::Water => 1,
-        }));
-        fs::write(format!("assets/maps/{}.data", self.id), map_data).map_err(|e| anyhow!(e))
+        write_json(
+            format!("assets/maps/{}.json", self.id),
+            &json!({
+                "version": FORMAT_VERSION,
+                "top": f32_value(self.top),
+                "left": f32_value(self.left),
+                "width": f32_value(self.width),
+                "height": f32_value(self.height),
+                "cols": self.cols,
+                "rows": self.rows.iter().map(|row| row.name()).collect::<Vec<&str>>(),
+            }),
+        )?;
+        remove_legacy(format!("assets/maps/{}.data", self.id))
     }
 }
-impl Level {
-    pub fn save_config(&self) -> Result<()> {
-        let mut level_data = Vec::with_capacity(32);
-        level_data.push(self.map.id);
-        level_data.extend(self.money.to_le_bytes());
-        level_data.push(self.spawn_waits.len() as u8);
-        level_data.extend(self.spawn_waits.iter().map(|w| w.as_secs() as u8));
-        level_data.extend(self.spawn_zombies.iter().flat_map(|z| {
-            let mut zombies: HashMap<u8, u8> = HashMap::with_capacity(4);
-            for z_id in z.iter().map(|z| z.0) {
-                if let Some(zz) = zombies.get_mut(&z_id) {
-                    *zz += 1;
-                } else {
-                    zombies.insert(z_id, 1);
-                }
-            }
-            let mut z_data = Vec::with_capacity(8);
-            z_data.push(zombies.len() as u8);
-            z_data.extend(zombies.into_iter().flat_map(|(k, v)| [k, v]));
-            z_data
-        }));
-        fs::write(format!("levels/{}.data", self.id), level_data).map_err(|e| anyhow!(e))
-    }
-    pub fn load(level: u8) -> Result<Self> {
-        let mut level_data = fs::read(format!("levels/{level}.data")).map_err(|e| anyhow!(e))?;
-
-        let map = Map::load(level_data.remove(0))?;
-        let rows = map.rows.len();
-
-        let money = u32::from_le_bytes([
-            level_data.remove(0),
-            level_data.remove(0),
-            level_data.remove(0),
-            level_data.remove(0),
-        ]);
-        let waves = level_data.remove(0).into();
-
-        let spawn_waits = level_data
-            .drain(0..waves)
-            .map(|secs| Duration::from_secs(secs as u64))
-            .collect();
-
-        let min_x = map.left + map.width - 305. / 1280.;
-        let min_y = map.top + map.height / rows as f32;
-        let max_y = map.top + map.height;
+
+pub struct LevelFile {
+    pub map: u8,
+    pub money: u32,
+    pub plants: Vec<u8>,
+    pub spawn_waits: Vec<Duration>,
+    pub spawn_zombies: Vec<Vec<(u8, Option<u8>)>>,
+    pub seed: Option<u64>,
+}
+
+impl LevelFile {
+    pub fn load(
+        level: u8,
+        zombie_registry: &ZombieRegistry,
+        plant_registry: &PlantRegistry,
+    ) -> Result<Self, LoadError> {
+        let file = format!("levels/{level}.json");
+        if !Path::new(&file).exists() {
+            return Self::load_legacy(level, zombie_registry, plant_registry);
+        }
+        let json = read_json(&file)?;
+        let json = Field::root(&file, &json);
+        check_version(&json)?;
+        let waves = json.get("waves").items()?;
+        let map = json.get("map").u8()?;
+        let map_rows = Map::load(map)?.rows;
+        Ok(Self {
+            map,
+            money: json.get("money").u32()?,
+            plants: match json.get("plants").optional() {
+                Some(plants) => plants
+                    .items()?
+                    .iter()
+                    .map(|plant| {
+                        plant_registry
+                            .id(plant.str()?)
+                            .ok_or_else(|| plant.invalid("a plant type"))
+                    })
+                    .collect::<Result<Vec<u8>, LoadError>>()?,
+                None => plant_registry.ids(),
+            },
+            spawn_waits: waves
+                .iter()
+                .map(|wave| Ok(Duration::from_millis(wave.get("wait_ms").u64()?)))
+                .collect::<Result<Vec<Duration>, LoadError>>()?,
+            spawn_zombies: waves
+                .iter()
+                .map(|wave| {
+                    let mut zombies = Vec::new();
+                    for group in wave.get("zombies").items()? {
+                        let zombie_type = group.get("type");
+                        let id = zombie_registry
+                            .id(zombie_type.str()?)
+                            .ok_or_else(|| zombie_type.invalid("a zombie type"))?;
+                        let rows = &zombie_registry.zombies[id as usize].rows;
+                        let lane = match group.get("lane").optional() {
+                            Some(lane_field) => {
+                                let lane = lane_field.u8()?;
+                                if !fits_lane(&map_rows, rows, Some(lane)) {
+                                    return Err(lane_field
+                                        .invalid("a lane inside the map the zombie can move in"));
+                                }
+                                Some(lane)
+                            }
+                            None => {
+                                if !fits_lane(&map_rows, rows, None) {
+                                    return Err(zombie_type
+                                        .invalid("a zombie type that can move in the map"));
+                                }
+                                None
+                            }
+                        };
+                        for _ in 0..group.get("count").u64()? {
+                            zombies.push((id, lane));
+                        }
+                    }
+                    Ok(zombies)
+                })
+                .collect::<Result<Vec<Vec<(u8, Option<u8>)>>, LoadError>>()?,
+            seed: json.get("seed").optional().map(|s| s.u64()).transpose()?,
+        })
+    }
+
+    pub fn load_legacy(
+        level: u8,
+        zombie_registry: &ZombieRegistry,
+        plant_registry: &PlantRegistry,
+    ) -> Result<Self, LoadError> {
+        let mut level_data = ByteReader::open(format!("levels/{level}.data"))?;
+
+        let map = level_data.u8("the map id")?;
+        let map_rows = Map::load(map)?.rows;
+        let money = u32::from_le_bytes(level_data.bytes("the starting money")?);
+        let waves = level_data.u8("the waves count")?;
+
+        let spawn_waits = (0..waves)
+            .map(|_| Ok(Duration::from_secs(level_data.u8("a wave time")? as u64)))
+            .collect::<Result<Vec<Duration>, LoadError>>()?;
+
         let spawn_zombies = (0..waves)
             .map(|_| {
-                let types = level_data.remove(0).into();
-                let zombies = generate_zombies_wave(&level_data, types, min_x, min_y, max_y);
-                level_data.drain(0..types * 2);
-                zombies
+                let mut zombies = Vec::new();
+                for _ in 0..level_data.u8("the zombie types count")? {
+                    let legacy_id = level_data.u8("a zombie id")?;
+                    let id = LEGACY_ZOMBIES
+                        .get(legacy_id as usize)
+                        .and_then(|name| zombie_registry.id(name))
+                        .ok_or_else(|| level_data.invalid_byte(legacy_id, "a zombie id"))?;
+                    if !fits_lane(&map_rows, &zombie_registry.zombies[id as usize].rows, None) {
+                        return Err(level_data
+                            .invalid_byte(legacy_id, "a zombie id that can move in the map"));
+                    }
+                    for _ in 0..level_data.u8("a zombie count")? {
+                        zombies.push((id, None));
+                    }
+                }
+                Ok(zombies)
             })
-            .collect();
+            .collect::<Result<Vec<Vec<(u8, Option<u8>)>>, LoadError>>()?;
 
-        if !level_data.is_empty() {
-            return Err(anyhow!(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Wrong format".to_owned(),
-            )));
-        }
+        let seed = if level_data.is_empty() {
+            None
+        } else {
+            Some(u64::from_le_bytes(level_data.bytes("the level seed")?))
+        };
+        level_data.end()?;
 
-        Ok(Level::new(
-            level,
+        Ok(Self {
             map,
-            rows,
             money,
+            plants: LEGACY_PLANTS
+                .iter()
+                .filter_map(|name| plant_registry.id(name))
+                .collect(),
             spawn_waits,
             spawn_zombies,
-        ))
+            seed,
+        })
+    }
+
+    pub fn save(
+        &self,
+        level: u8,
+        zombie_registry: &ZombieRegistry,
+        plant_registry: &PlantRegistry,
+    ) -> Result<()> {
+        let waves = self
+            .spawn_waits
+            .iter()
+            .zip(self.spawn_zombies.iter())
+            .map(|(wait, zombies)| {
+                let mut groups: Vec<(u8, Option<u8>, u32)> = Vec::with_capacity(4);
+                for &(id, lane) in zombies {
+                    if let Some(group) = groups.iter_mut().find(|g| g.0 == id && g.1 == lane) {
+                        group.2 += 1;
+                    } else {
+                        groups.push((id, lane, 1));
+                    }
+                }
+                let groups = groups
+                    .into_iter()
+                    .map(|(id, lane, count)| {
+                        let mut group = json!({
+                            "type": zombie_registry.name(id).ok_or(anyhow!("zombie id not found"))?,
+                            "count": count,
+                        });
+                        if let Some(lane) = lane {
+                            group["lane"] = lane.into();
+                        }
+                        Ok(group)
+                    })
+                    .collect::<Result<Vec<Value>>>()?;
+                Ok(json!({
+                    "wait_ms": wait.as_millis() as u64,
+                    "zombies": groups,
+                }))
+            })
+            .collect::<Result<Vec<Value>>>()?;
+        let mut json = json!({
+            "version": FORMAT_VERSION,
+            "map": self.map,
+            "money": self.money,
+            "plants": self
+                .plants
+                .iter()
+                .map(|&id| plant_registry.name(id).ok_or(anyhow!("plant id not found")))
+                .collect::<Result<Vec<&str>>>()?,
+            "waves": waves,
+        });
+        if let Some(seed) = self.seed {
+            json["seed"] = seed.into();
+        }
+        write_json(format!("levels/{level}.json"), &json)?;
+        remove_legacy(format!("levels/{level}.data"))
     }
 }
 
-fn generate_zombies_wave(
-    data: &[u8],
-    types: usize,
-    min_x: f32,
-    min_y: f32,
-    max_y: f32,
-) -> Vec<(u8, f32, f32)> {
-    let mut rng = rand::rng();
-    data.chunks_exact(2)
-        .take(types)
-        .flat_map(|bytes| {
-            let (width, height) = zombie_from_id(bytes[0]).rect(0.).size();
-            (0..bytes[1])
-                .map(|_| {
-                    (
-                        bytes[0],
-                        rng.random_range((min_x)..=(1. - width)),
-                        rng.random_range((min_y - height)..=(max_y - height)),
-                    )
-                })
-                .collect::<Vec<(u8, f32, f32)>>()
+pub fn upgrade_legacy_files() -> Result<()> {
+    let zombie_registry = ZombieRegistry::load()?;
+    let plant_registry = PlantRegistry::load()?;
+    for map in legacy_ids("assets/maps")? {
+        Map::load_legacy(map)?.save()?;
+    }
+    for level in legacy_ids("levels")? {
+        LevelFile::load_legacy(level, &zombie_registry, &plant_registry)?.save(
+            level,
+            &zombie_registry,
+            &plant_registry,
+        )?;
+    }
+    Ok(())
+}
+
+fn legacy_ids(dir: &str) -> Result<Vec<u8>> {
+    fs::read_dir(dir)
+        .map_err(|e| anyhow!(e))?
+        .filter_map(|entry| {
+            let path = entry.map_err(|e| anyhow!(e)).ok()?.path();
+            if path.extension()? != "data" {
+                return None;
+            }
+            Some(
+                path.file_stem()?
+                    .to_str()?
+                    .parse::<u8>()
+                    .map_err(|e| anyhow!(e)),
+            )
         })
         .collect()
 }
+
+pub(super) fn write_json(path: String, json: &Value) -> Result<()> {
+    let text = serde_json::to_string_pretty(json).map_err(|e| anyhow!(e))?;
+    fs::write(path, text + "\n").map_err(|e| anyhow!(e))
+}
+
+fn remove_legacy(path: String) -> Result<()> {
+    if Path::new(&path).exists() {
+        fs::remove_file(path).map_err(|e| anyhow!(e))?;
+    }
+    Ok(())
+}
+
+pub(super) fn fits_lane(map_rows: &[RowType], zombie_rows: &[RowType], lane: Option<u8>) -> bool {
+    match lane {
+        Some(lane) => map_rows
+            .get(lane as usize)
+            .is_some_and(|row| zombie_rows.contains(row)),
+        None => map_rows.iter().any(|row| zombie_rows.contains(row)),
+    }
+}
+
+pub(super) fn check_version(json: &Field) -> Result<(), LoadError> {
+    let version = json.get("version");
+    if version.u64()? != FORMAT_VERSION {
+        return Err(version.invalid("format version 1"));
+    }
+    Ok(())
+}
+
+impl Level {
+    pub fn save_config(&self) -> Result<()> {
+        let sim = &self.sim;
+        LevelFile {
+            map: sim.map.id,
+            money: sim.money,
+            plants: sim
+                .shop_plants
+                .iter()
+                .map(|plant| plant.definition().id)
+                .collect(),
+            spawn_waits: sim.spawn_waits.clone(),
+            spawn_zombies: sim
+                .spawn_zombies
+                .iter()
+                .map(|wave| wave.iter().map(|z| (z.id, z.lane)).collect())
+                .collect(),
+            seed: sim.level_seed,
+        }
+        .save(self.id, sim.zombie_registry, sim.plant_registry)
+    }
+    pub fn load(
+        level: u8,
+        seed: Option<u64>,
+        zombie_registry: &'static ZombieRegistry,
+        plant_registry: &'static PlantRegistry,
+    ) -> Result<Self, LoadError> {
+        Ok(Level::new(
+            level,
+            Simulation::load(level, seed, zombie_registry, plant_registry)?,
+        ))
+    }
+}
+impl Simulation {
+    pub fn load(
+        level: u8,
+        seed: Option<u64>,
+        zombie_registry: &'static ZombieRegistry,
+        plant_registry: &'static PlantRegistry,
+    ) -> Result<Self, LoadError> {
+        let file = LevelFile::load(level, zombie_registry, plant_registry)?;
+        let map = Map::load(file.map)?;
+        let sim = Simulation::new(
+            zombie_registry,
+            plant_registry,
+            map,
+            file.money,
+            &file.plants,
+            file.spawn_waits,
+            file.spawn_zombies,
+            seed.or(file.seed).unwrap_or_else(rand::random),
+        )
+        .ok_or_else(|| LoadError::InvalidField {
+            file: format!("levels/{level}.json"),
+            field: "waves".to_owned(),
+            expected: "zombies that can move in the map",
+        })?;
+
+        Ok(Simulation {
+            level_seed: file.seed,
+            ..sim
+        })
+    }
+}
@@ -1,6 +1,11 @@
 use anyhow::{anyhow, Result};
 use red_sdl::{missing::rect::scale, refs::Ref};
-use sdl2::{render::Canvas, video::Window};
+use sdl2::{
+    pixels::Color,
+    rect::FRect,
+    render::{BlendMode, Canvas},
+    video::Window,
+};
 
 use crate::{sun::Sun, zombie::Zombie, State};
 
@@ -12,7 +17,8 @@ impl Level {
         canvas: &mut Canvas<Window>,
         state: &'static State,
     ) -> Result<()> {
-        for (y, zombies) in self.zombies.iter().enumerate() {
+        let map = &self.sim.map;
+        for (y, zombies) in self.sim.zombies.iter().enumerate() {
             let mut zombies: Vec<&dyn Zombie> =
                 zombies.iter().map(|zombie| zombie.as_ref()).collect();
             zombies.sort_by(|&z1, &z2| z1.rect(0.).left().total_cmp(&z2.rect(0.).left()));
@@ -24,8 +30,7 @@ impl Level {
                         scale(
                             self.surface,
                             zombie.rect(
-                                self.map.pos_to_coord_y(y) + self.map.row_heigth()
-                                    - zombie.rect(0.).height(),
+                                map.pos_to_coord_y(y) + map.row_heigth() - zombie.rect(0.).height(),
                             ),
                         ),
                     )
@@ -40,7 +45,8 @@ impl Level {
         canvas: &mut Canvas<Window>,
         state: &'static State,
     ) -> Result<()> {
-        for (y, projs) in self.projectiles.iter().enumerate() {
+        let map = &self.sim.map;
+        for (y, projs) in self.sim.projectiles.iter().enumerate() {
             for proj in projs {
                 canvas
                     .copy_f(
@@ -49,8 +55,9 @@ impl Level {
                         scale(
                             self.surface,
                             proj.rect(
-                                self.map.pos_to_coord_y(y) + self.map.row_heigth() / 2.
-                                    - proj.rect(0.).height() / 2.,
+                                map.pos_to_coord_y(y) + map.row_heigth() / 2.
+                                    - proj.rect(0.).height() / 2.
+                                    - proj.height(),
                             ),
                         ),
                     )
@@ -60,11 +67,46 @@ impl Level {
         Ok(())
     }
     pub fn draw_suns(&'static self, canvas: &mut Canvas<Window>, state: Ref<State>) -> Result<()> {
-        for sun in self.suns.iter() {
+        for sun in self.sim.suns.iter() {
             canvas
                 .copy_f(Sun::texture(state), None, scale(self.surface, sun.rect()))
                 .map_err(|e| anyhow!(e))?;
         }
         Ok(())
     }
+
+    pub fn draw_shovel(
+        &'static self,
+        canvas: &mut Canvas<Window>,
+        state: &'static State,
+        x: f32,
+        y: f32,
+    ) -> Result<()> {
+        let map = &self.sim.map;
+        if let (Some(col), Some(row)) = (map.coord_to_pos_x(x), map.coord_to_pos_y(y)) {
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(Color::RGBA(255, 0, 0, 100));
+            canvas
+                .fill_frect(scale(
+                    self.surface,
+                    FRect::new(
+                        map.pos_to_coord_x(col),
+                        map.pos_to_coord_y(row),
+                        map.col_width(),
+                        map.row_heigth(),
+                    ),
+                ))
+                .map_err(|e| anyhow!(e))?;
+            canvas.set_blend_mode(BlendMode::None);
+        }
+        state.texts().shovel.draw(
+            canvas,
+            None,
+            scale(
+                self.surface,
+                FRect::new(x - 40. / 1280., y - 15. / 720., 80. / 1280., 30. / 720.),
+            ),
+            Color::WHITE,
+        )
+    }
 }
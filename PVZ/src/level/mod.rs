@@ -1,5 +1,4 @@
 use anyhow::{anyhow, Result};
-use config::Map;
 use red_sdl::{
     event::Event,
     missing::{rect::scale, ui_string::UIString},
@@ -25,40 +24,35 @@ use std::{collections::HashMap, time::Duration};
 mod collision;
 pub mod config;
 mod draws;
+pub mod parse;
+pub mod simulation;
+pub mod suspend;
+#[cfg(test)]
+mod tests;
 mod updates;
 
 use crate::{
     default_button,
     map_plant::MapPlant,
-    plants::{
-        nenuphar::Nenuphar, peashooter::PeaShooter, sunflower::Sunflower,
-        triple_peashooter::PlantTriple, Plant,
-    },
-    projectile::{DamageType, Projectile},
+    replay::{Action, Replay},
     shop_plant::ShopPlant,
-    sun::Sun,
     win::Win,
-    zombie::{zombie_from_id, Zombie},
     State,
 };
+use simulation::{Command, Simulation, SpawnZombie};
 
 pub struct Level {
     pub id: u8,
     pub started: Option<Grid<Level, State, LevelShopElement>>,
     pub surface: FRect,
-    pub suns: Vec<Sun>,
-    pub next_sun: Duration,
     pub map_plants: Grid<Level, State, MapPlant>,
-    pub zombies: Vec<Vec<Box<dyn Zombie>>>,
-    pub projectiles: Vec<Vec<Box<dyn Projectile>>>,
-    pub map: Map,
-    pub spawn_waits: Vec<Duration>,
-    #[allow(clippy::type_complexity)]
-    pub spawn_zombies: Vec<Vec<(u8, f32, f32)>>,
-    pub shop_plants: Vec<Box<dyn Plant>>,
-    pub dragging: Option<(f32, f32, Box<dyn Plant>)>,
-    pub money: u32,
-    pub end: Option<bool>,
+    pub dragging: Option<(f32, f32, usize)>,
+    pub shovel: Option<(f32, f32)>,
+    pub sim: Simulation,
+    pub record: Replay,
+    pub playback: Option<Replay>,
+    pub resumed: bool,
+    record_saved: bool,
 }
 
 #[derive(UserControl)]
@@ -70,22 +64,14 @@ pub enum LevelShopElement {
 }
 
 impl Level {
-    fn new(
-        level: u8,
-        map: Map,
-        rows: usize,
-        money: u32,
-        spawn_waits: Vec<Duration>,
-        spawn_zombies: Vec<Vec<(u8, f32, f32)>>,
-    ) -> Self {
+    fn new(level: u8, sim: Simulation) -> Self {
+        let map = &sim.map;
         let (c_width, c_height) = (map.col_width(), map.row_heigth());
-        let rows_type = &map.rows;
+        let rows = map.rows.len();
         Self {
             id: level,
             started: None,
             surface: zero(),
-            suns: Vec::with_capacity(4),
-            next_sun: Duration::new(5, 0),
             map_plants: Grid::new(
                 {
                     let mut cols: Vec<ColType> = (0..map.cols)
@@ -102,7 +88,7 @@ impl Level {
                     cols
                 },
                 {
-                    let mut rows: Vec<RowType> = (0..rows_type.len())
+                    let mut rows: Vec<RowType> = (0..rows)
                         .flat_map(|_| {
                             [
                                 RowType::Ratio(5. / 720.),
@@ -123,69 +109,86 @@ impl Level {
                                 y: y * 3 + 2,
                             },
                             MapPlant {
-                                row_type: rows_type[y],
-                                plant: None,
+                                x,
+                                y,
                                 surface: zero(),
                             },
                         )
                     })
                 })),
             ),
-            zombies: (0..rows).map(|_| Vec::with_capacity(16)).collect(),
-            projectiles: (0..rows).map(|_| Vec::with_capacity(4)).collect(),
-            map,
-            spawn_waits,
-            spawn_zombies,
-            shop_plants: vec![
-                Box::new(Nenuphar::new()),
-                Box::new(Sunflower::new()),
-                Box::new(PeaShooter::new(DamageType::Normal)),
-                Box::new(PeaShooter::new(DamageType::Ice)),
-                Box::new(PeaShooter::new(DamageType::Fire)),
-                Box::new(PlantTriple::new()),
-            ],
             dragging: None,
-            money,
-            end: None,
+            shovel: None,
+            record: Replay::new(level, sim.seed),
+            playback: None,
+            resumed: false,
+            record_saved: false,
+            sim,
+        }
+    }
+
+    pub fn save_record(&mut self) -> Result<()> {
+        if self.started.is_some() && self.playback.is_none() && !self.resumed && !self.record_saved
+        {
+            self.record.save()?;
+            self.record_saved = true;
         }
+        Ok(())
     }
 
-    fn take_plant(mut this: MutRef<Self>, plant: Box<dyn Plant>, x: f32, y: f32) {
-        if this.dragging.is_none() {
+    fn take_plant(mut this: MutRef<Self>, plant: usize, x: f32, y: f32) {
+        if this.dragging.is_none() && this.shovel.is_none() && this.playback.is_none() {
             this.dragging = Some((x, y, plant));
         }
     }
 
+    fn take_shovel(mut this: MutRef<Self>) {
+        if this.dragging.is_none() && this.playback.is_none() {
+            this.shovel = Some((0., 0.));
+        }
+    }
+
+    fn drop_shovel(&mut self, x: f32, y: f32) {
+        if self.shovel.take().is_some() {
+            if let Some(x) = self.sim.map.coord_to_pos_x(x / self.surface.width()) {
+                if let Some(y) = self.sim.map.coord_to_pos_y(y / self.surface.height()) {
+                    self.command(Command::RemovePlant { x, y });
+                }
+            }
+        }
+    }
+
     fn drop_plant(&mut self, x: f32, y: f32) {
-        if let Some((_, _, plant)) = self.dragging.as_ref() {
-            if self.money >= plant.cost() {
-                if let Some(x) = self.map.coord_to_pos_x(x / self.surface.width()) {
-                    if let Some(y) = self.map.coord_to_pos_y(y / self.surface.height()) {
-                        if let Some(slot) = self.map_plants.get_element_mut(x * 3 + 2, y * 3 + 2) {
-                            match self.map.rows[y] {
-                                config::RowType::Grass => {
-                                    if !plant.is_nenuphar() && slot.plant.is_none() {
-                                        self.money -= plant.cost();
-                                        slot.plant = Some(plant.as_ref().clone());
-                                    }
-                                }
-                                config::RowType::Water => {
-                                    if plant.can_go_in_water() && slot.plant.is_none() {
-                                        self.money -= plant.cost();
-                                        slot.plant = Some(plant.as_ref().clone());
-                                    } else if let Some(nen) = slot.plant.as_ref() {
-                                        if nen.is_nenuphar() {
-                                            self.money -= plant.cost();
-                                            slot.plant = Some(plant.as_ref().clone());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+        if let Some((_, _, plant)) = self.dragging.take() {
+            if !self.sim.is_recharged(plant) {
+                return;
+            }
+            if let Some(x) = self.sim.map.coord_to_pos_x(x / self.surface.width()) {
+                if let Some(y) = self.sim.map.coord_to_pos_y(y / self.surface.height()) {
+                    self.command(Command::PlacePlant { plant, x, y });
                 }
             }
-            self.dragging = None;
+        }
+    }
+
+    fn command(&mut self, command: Command) {
+        if self.sim.command(command) {
+            self.record.record(Action::Command(command));
+        }
+    }
+
+    fn apply(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        state: MutRef<State>,
+        action: Action,
+    ) -> Result<()> {
+        match action {
+            Action::Start => Self::start(this, canvas, state),
+            Action::Command(command) => {
+                this.command(command);
+                Ok(())
+            }
         }
     }
 
@@ -197,14 +200,19 @@ impl Level {
         if this.started.is_some() {
             return Ok(());
         }
+        let card = (1185. / this.sim.shop_plants.len() as f32 - 10.).min(132.5);
         let mut rows: Vec<RowType> = this
+            .sim
             .shop_plants
             .iter()
-            .flat_map(|_| [RowType::Ratio(132.5), RowType::Ratio(10.)])
+            .flat_map(|_| [RowType::Ratio(card), RowType::Ratio(10.)])
             .collect();
         rows.insert(0, RowType::Ratio(10.));
         let moneyid = rows.len();
         rows.push(RowType::Ratio(37.5));
+        rows.push(RowType::Ratio(10.));
+        let shovelid = rows.len();
+        rows.push(RowType::Ratio(37.5));
         let remain: f32 = rows
             .iter()
             .map(|r| if let RowType::Ratio(p) = r { *p } else { 0. })
@@ -213,25 +221,36 @@ impl Level {
             rows.push(RowType::Ratio(1280. - remain));
         }
 
-        let mut element =
-            HashMap::from_iter(this.shop_plants.iter().enumerate().map(|(i, plant)| {
-                (
-                    Pos { x: 1, y: i * 2 + 1 },
-                    ShopPlant::new(Self::take_plant, plant.as_ref().clone()).into(),
-                )
-            }));
+        let mut element = HashMap::from_iter((0..this.sim.shop_plants.len()).map(|i| {
+            (
+                Pos { x: 1, y: i * 2 + 1 },
+                ShopPlant::new(Self::take_plant, i).into(),
+            )
+        }));
         element.insert(
             Pos { x: 1, y: moneyid },
             default_button()
                 .text(Box::new(|_, _self: Ref<Level>, _state: Ref<State>| {
                     UIString::new(
                         _state.as_ref().textures().font(),
-                        format!("{}$", _self.money),
+                        format!("{}$", _self.sim.money),
                     )
                     .map(|s| (s, Color::WHITE))
                 }))
                 .into(),
         );
+        element.insert(
+            Pos { x: 1, y: shovelid },
+            default_button()
+                .action(Box::new(|_, _self: MutRef<Level>, _, _| {
+                    Self::take_shovel(_self);
+                    Ok(())
+                }))
+                .text(Box::new(|_, _, _state: Ref<State>| {
+                    Ok((Some(_state.as_ref().texts().shovel.clone()), Color::WHITE))
+                }))
+                .into(),
+        );
         let mut grid = Grid::new(
             vec![
                 ColType::Ratio(10.),
@@ -263,6 +282,7 @@ impl Level {
             state,
         )?;
         this.started = Some(grid);
+        this.record.record(Action::Start);
         Ok(())
     }
 }
@@ -291,11 +311,12 @@ impl UserControl<Win, State> for Level {
             Event::KeyDown {
                 keycode: Some(Keycode::Space),
                 ..
-            } => {
+            } if this.playback.is_none() => {
                 Self::start(this, canvas, state)?;
             }
-            Event::MouseMotion { x, y, .. } => {
+            Event::MouseMotion { x, y, .. } if this.playback.is_none() => {
                 for i in this
+                    .sim
                     .suns
                     .iter()
                     .enumerate()
@@ -312,13 +333,16 @@ impl UserControl<Win, State> for Level {
                     .rev()
                     .collect::<Vec<usize>>()
                 {
-                    this.money += 25;
-                    this.suns.remove(i);
+                    this.command(Command::CollectSun(i));
                 }
                 if let Some(plant) = this.as_mut().dragging.as_mut() {
                     plant.0 = x / this.surface.width();
                     plant.1 = y / this.surface.height();
                 }
+                if let Some(shovel) = this.as_mut().shovel.as_mut() {
+                    shovel.0 = x / this.surface.width();
+                    shovel.1 = y / this.surface.height();
+                }
             }
             Event::MouseButtonUp {
                 mouse_btn: MouseButton::Left,
@@ -327,6 +351,7 @@ impl UserControl<Win, State> for Level {
                 ..
             } => {
                 this.drop_plant(x, y);
+                this.drop_shovel(x, y);
             }
             _ => {}
         }
@@ -340,34 +365,35 @@ impl UserControl<Win, State> for Level {
     fn update(
         mut this: MutRef<Self>,
         canvas: &Canvas<Window>,
-        elapsed: Duration,
+        mut elapsed: Duration,
         _: MutRef<Win>,
-        state: MutRef<State>,
+        mut state: MutRef<State>,
     ) -> Result<()> {
-        if this.started.is_none() {
-            return Ok(());
+        if let Some(playback) = this.as_mut().playback.as_mut() {
+            let Some(frame) = playback.next_frame() else {
+                return Ok(());
+            };
+            for &action in frame.actions.iter() {
+                Self::apply(this, canvas, state, action)?;
+            }
+            elapsed = frame.elapsed;
         }
-        if this.end.is_some() {
+        if this.started.is_none() || this.sim.end.is_some() {
             return Ok(());
         }
-        if !this.zombies.iter().flatten().any(|_| true) && this.spawn_waits.is_empty() {
-            this.end = Some(true);
-            return Ok(());
+        this.sim.update(elapsed)?;
+        this.record.record_frame(elapsed);
+        if this.sim.end.is_some() {
+            this.save_record()?;
         }
-        for (_, mut plant) in this.map_plants.iter_mut() {
-            if let Some(plant) = plant.plant.as_mut() {
-                plant.update(elapsed)?;
-            }
+        if this.sim.end == Some(true) && this.playback.is_none() {
+            state.as_mut().save.complete_level(
+                this.id,
+                this.sim.time,
+                this.sim.remaining_lawn(),
+            )?;
         }
         UserControl::update((&mut this.map_plants).into(), canvas, elapsed, this, state)?;
-        this.as_mut().update_zombies(elapsed)?;
-        if let Some(false) = this.end {
-            return Ok(());
-        }
-        this.as_mut().update_projectiles(elapsed)?;
-        this.as_mut().update_suns(elapsed)?;
-        this.as_mut().spawn_projectiles();
-        this.as_mut().update_zombie_wave(elapsed);
         if let Some(started) = this.as_mut().started.as_mut() {
             UserControl::update(started.into(), canvas, elapsed, this, state)?;
         }
@@ -382,7 +408,7 @@ impl UserControl<Win, State> for Level {
     ) -> Result<()> {
         canvas
             .copy(
-                state.as_ref().textures().map(this.map.id as usize),
+                state.as_ref().textures().map(this.sim.map.id as usize),
                 Some(Rect::new(
                     if this.started.is_none() { 238 } else { 0 },
                     0,
@@ -399,7 +425,7 @@ impl UserControl<Win, State> for Level {
             this.as_ref().draw_projectiles(canvas, state.as_ref())?;
             UserControl::draw(started.into(), canvas, this, state)?;
             this.as_ref().draw_suns(canvas, state)?;
-            if let Some(end) = this.end {
+            if let Some(end) = this.sim.end {
                 if end {
                     &state.texts().win
                 } else {
@@ -412,36 +438,41 @@ impl UserControl<Win, State> for Level {
                     Color::WHITE,
                 )?;
             }
-            if let Some((x, y, plant)) = this.as_ref().dragging.as_ref() {
+            if let Some(&(x, y, plant)) = this.as_ref().dragging.as_ref() {
+                let sim = &this.as_ref().sim;
+                let map = &sim.map;
                 canvas
                     .copy_f(
-                        plant.texture(state),
+                        sim.shop_plants[plant].texture(state),
                         None,
                         scale(
                             this.surface,
                             FRect::new(
-                                x - (this.map.col_width() - 10. / 1280.) / 2.,
-                                y - (this.map.row_heigth() - 10. / 720.) / 2.,
-                                this.map.col_width() - 10. / 1280.,
-                                this.map.row_heigth() - 10. / 720.,
+                                x - (map.col_width() - 10. / 1280.) / 2.,
+                                y - (map.row_heigth() - 10. / 720.) / 2.,
+                                map.col_width() - 10. / 1280.,
+                                map.row_heigth() - 10. / 720.,
                             ),
                         ),
                     )
                     .map_err(|e| anyhow!(e))?;
             }
+            if let Some(&(x, y)) = this.as_ref().shovel.as_ref() {
+                this.as_ref().draw_shovel(canvas, state.as_ref(), x, y)?;
+            }
             return Ok(());
         }
 
-        let mut t: Vec<&(u8, f32, f32)> = this.spawn_zombies.iter().flatten().collect();
-        t.sort_by(|(_, _, y1), (_, _, y2)| y1.total_cmp(y2));
-        for &(z, x, y) in t {
-            let mut z = zombie_from_id(z);
-            z.set_x(x);
+        let mut t: Vec<&SpawnZombie> = this.sim.spawn_zombies.iter().flatten().collect();
+        t.sort_by(|z1, z2| z1.y.total_cmp(&z2.y));
+        for spawn in t {
+            let mut z = this.sim.zombie_registry.spawn(spawn.id);
+            z.set_x(spawn.x);
             canvas
                 .copy_f(
                     z.texture(state.as_ref().textures()),
                     None,
-                    scale(this.surface, z.rect(y)),
+                    scale(this.surface, z.rect(spawn.y)),
                 )
                 .map_err(|e| anyhow!(e))?;
         }
@@ -0,0 +1,266 @@
+use serde_json::{Number, Value};
+use std::{error::Error, fmt, fs, io, time::Duration};
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        file: String,
+        error: io::Error,
+    },
+    UnexpectedEnd {
+        file: String,
+        offset: usize,
+        expected: &'static str,
+    },
+    InvalidByte {
+        file: String,
+        offset: usize,
+        value: u8,
+        expected: &'static str,
+    },
+    TrailingData {
+        file: String,
+        offset: usize,
+    },
+    Json {
+        file: String,
+        error: serde_json::Error,
+    },
+    MissingField {
+        file: String,
+        field: String,
+    },
+    InvalidField {
+        file: String,
+        field: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io { file, error } => write!(f, "{file}: {error}"),
+            LoadError::UnexpectedEnd {
+                file,
+                offset,
+                expected,
+            } => write!(
+                f,
+                "{file} at byte {offset}: expected {expected}, found end of file"
+            ),
+            LoadError::InvalidByte {
+                file,
+                offset,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{file} at byte {offset}: expected {expected}, found {value}"
+            ),
+            LoadError::TrailingData { file, offset } => {
+                write!(f, "{file} at byte {offset}: expected end of file")
+            }
+            LoadError::Json { file, error } => write!(f, "{file}: {error}"),
+            LoadError::MissingField { file, field } => write!(f, "{file}: missing field {field}"),
+            LoadError::InvalidField {
+                file,
+                field,
+                expected,
+            } => write!(f, "{file} at {field}: expected {expected}"),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io { error, .. } => Some(error),
+            LoadError::Json { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+pub struct ByteReader {
+    file: String,
+    data: Vec<u8>,
+    offset: usize,
+}
+
+impl ByteReader {
+    pub fn open(file: String) -> Result<Self, LoadError> {
+        match fs::read(&file) {
+            Ok(data) => Ok(Self {
+                file,
+                data,
+                offset: 0,
+            }),
+            Err(error) => Err(LoadError::Io { file, error }),
+        }
+    }
+
+    pub fn bytes<const N: usize>(&mut self, expected: &'static str) -> Result<[u8; N], LoadError> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + N)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| LoadError::UnexpectedEnd {
+                file: self.file.clone(),
+                offset: self.offset,
+                expected,
+            })?;
+        self.offset += N;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self, expected: &'static str) -> Result<u8, LoadError> {
+        self.bytes(expected).map(|[byte]| byte)
+    }
+
+    pub fn invalid_byte(&self, value: u8, expected: &'static str) -> LoadError {
+        LoadError::InvalidByte {
+            file: self.file.clone(),
+            offset: self.offset - 1,
+            value,
+            expected,
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    pub fn end(&self) -> Result<(), LoadError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(LoadError::TrailingData {
+                file: self.file.clone(),
+                offset: self.offset,
+            })
+        }
+    }
+}
+
+pub fn read_json(file: &str) -> Result<Value, LoadError> {
+    let text = fs::read_to_string(file).map_err(|error| LoadError::Io {
+        file: file.to_owned(),
+        error,
+    })?;
+    serde_json::from_str(text.as_str()).map_err(|error| LoadError::Json {
+        file: file.to_owned(),
+        error,
+    })
+}
+
+pub fn f32_value(v: f32) -> Value {
+    v.to_string()
+        .parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map_or(Value::Null, Value::Number)
+}
+
+pub fn duration_value(duration: Duration) -> Value {
+    (duration.as_nanos() as u64).into()
+}
+
+pub struct Field<'a> {
+    file: &'a str,
+    path: String,
+    value: Option<&'a Value>,
+}
+
+impl<'a> Field<'a> {
+    pub const fn root(file: &'a str, value: &'a Value) -> Self {
+        Self {
+            file,
+            path: String::new(),
+            value: Some(value),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Self {
+        Self {
+            file: self.file,
+            path: if self.path.is_empty() {
+                key.to_owned()
+            } else {
+                format!("{}.{key}", self.path)
+            },
+            value: self.value.and_then(|value| value.get(key)),
+        }
+    }
+
+    pub fn optional(&self) -> Option<&Self> {
+        self.value.filter(|value| !value.is_null()).map(|_| self)
+    }
+
+    pub fn items(&self) -> Result<Vec<Self>, LoadError> {
+        Ok(self
+            .value()?
+            .as_array()
+            .ok_or_else(|| self.invalid("an array"))?
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Self {
+                file: self.file,
+                path: format!("{}[{i}]", self.path),
+                value: Some(value),
+            })
+            .collect())
+    }
+
+    pub fn str(&self) -> Result<&'a str, LoadError> {
+        self.value()?
+            .as_str()
+            .ok_or_else(|| self.invalid("a string"))
+    }
+
+    pub fn u64(&self) -> Result<u64, LoadError> {
+        self.value()?
+            .as_u64()
+            .ok_or_else(|| self.invalid("a positive integer"))
+    }
+
+    pub fn u32(&self) -> Result<u32, LoadError> {
+        u32::try_from(self.u64()?).map_err(|_| self.invalid("an integer up to 4294967295"))
+    }
+
+    pub fn u8(&self) -> Result<u8, LoadError> {
+        u8::try_from(self.u64()?).map_err(|_| self.invalid("an integer up to 255"))
+    }
+
+    pub fn f32(&self) -> Result<f32, LoadError> {
+        self.value()?
+            .as_f64()
+            .map(|v| v as f32)
+            .ok_or_else(|| self.invalid("a number"))
+    }
+
+    pub fn duration(&self) -> Result<Duration, LoadError> {
+        self.u64().map(Duration::from_nanos)
+    }
+
+    pub fn bool(&self) -> Result<bool, LoadError> {
+        self.value()?
+            .as_bool()
+            .ok_or_else(|| self.invalid("a boolean"))
+    }
+
+    pub fn invalid(&self, expected: &'static str) -> LoadError {
+        LoadError::InvalidField {
+            file: self.file.to_owned(),
+            field: self.path.clone(),
+            expected,
+        }
+    }
+
+    fn value(&self) -> Result<&'a Value, LoadError> {
+        self.value.ok_or_else(|| LoadError::MissingField {
+            file: self.file.to_owned(),
+            field: self.path.clone(),
+        })
+    }
+}
@@ -0,0 +1,277 @@
+use anyhow::Result;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use std::time::Duration;
+
+use crate::{
+    plants::{registry::PlantRegistry, tile::Tile, Plant},
+    projectile::Projectile,
+    sun::Sun,
+    zombie::{registry::ZombieRegistry, Zombie},
+};
+
+use super::config::{fits_lane, Map};
+
+pub struct Simulation {
+    pub map: Map,
+    pub plants: Vec<Vec<Tile>>,
+    pub zombies: Vec<Vec<Box<dyn Zombie>>>,
+    pub projectiles: Vec<Vec<Box<dyn Projectile>>>,
+    pub suns: Vec<Sun>,
+    pub next_sun: Duration,
+    pub spawn_waits: Vec<Duration>,
+    pub spawn_zombies: Vec<Vec<SpawnZombie>>,
+    pub shop_plants: Vec<Box<dyn Plant>>,
+    pub shop_recharges: Vec<Duration>,
+    pub money: u32,
+    pub end: Option<bool>,
+    pub time: Duration,
+    pub closest_zombie: f32,
+    pub next_zombie_uid: u64,
+    pub seed: u64,
+    pub level_seed: Option<u64>,
+    pub rng: SimRng,
+    pub zombie_registry: &'static ZombieRegistry,
+    pub plant_registry: &'static PlantRegistry,
+}
+
+#[derive(Clone, Copy)]
+pub struct SpawnZombie {
+    pub id: u8,
+    pub lane: Option<u8>,
+    pub x: f32,
+    pub y: f32,
+}
+
+pub struct SimRng {
+    rng: StdRng,
+    draws: u64,
+}
+
+impl SimRng {
+    pub fn new(seed: u64, draws: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..draws {
+            rng.next_u32();
+        }
+        Self { rng, draws }
+    }
+
+    pub const fn draws(&self) -> u64 {
+        self.draws
+    }
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draws += 2;
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.draws += dst.len().div_ceil(4) as u64;
+        self.rng.fill_bytes(dst);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    PlacePlant { plant: usize, x: usize, y: usize },
+    RemovePlant { x: usize, y: usize },
+    CollectSun(usize),
+}
+
+impl Simulation {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        zombie_registry: &'static ZombieRegistry,
+        plant_registry: &'static PlantRegistry,
+        map: Map,
+        money: u32,
+        shop_plants: &[u8],
+        spawn_waits: Vec<Duration>,
+        spawn_zombies: Vec<Vec<(u8, Option<u8>)>>,
+        seed: u64,
+    ) -> Option<Self> {
+        let rows = map.rows.len();
+        let mut sim = Self {
+            plants: (0..rows)
+                .map(|_| (0..map.cols).map(|_| Tile::default()).collect())
+                .collect(),
+            zombies: (0..rows).map(|_| Vec::with_capacity(16)).collect(),
+            projectiles: (0..rows).map(|_| Vec::with_capacity(4)).collect(),
+            suns: Vec::with_capacity(4),
+            next_sun: Duration::new(5, 0),
+            map,
+            spawn_waits,
+            spawn_zombies: Vec::new(),
+            shop_plants: shop_plants
+                .iter()
+                .map(|&id| plant_registry.spawn(id))
+                .collect(),
+            shop_recharges: vec![Duration::ZERO; shop_plants.len()],
+            money,
+            end: None,
+            time: Duration::new(0, 0),
+            closest_zombie: 1.,
+            next_zombie_uid: 0,
+            seed,
+            level_seed: None,
+            rng: SimRng::new(seed, 0),
+            zombie_registry,
+            plant_registry,
+        };
+        sim.spawn_zombies = spawn_zombies
+            .iter()
+            .map(|zombies| sim.generate_zombies_wave(zombies))
+            .collect::<Option<_>>()?;
+        Some(sim)
+    }
+
+    pub(super) fn random_lane(&mut self, id: u8) -> Option<usize> {
+        let rows = &self.zombie_registry.zombies[id as usize].rows;
+        let lanes = self
+            .map
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| rows.contains(row))
+            .map(|(lane, _)| lane)
+            .collect::<Vec<usize>>();
+        if lanes.is_empty() {
+            return None;
+        }
+        Some(lanes[self.rng.random_range(0..lanes.len())])
+    }
+
+    fn generate_zombies_wave(&mut self, zombies: &[(u8, Option<u8>)]) -> Option<Vec<SpawnZombie>> {
+        let min_x = self.map.left + self.map.width - 305. / 1280.;
+        zombies
+            .iter()
+            .map(|&(id, lane)| {
+                let (width, height) = self.zombie_registry.spawn(id).rect(0.).size();
+                let row = match lane {
+                    Some(lane) => fits_lane(
+                        &self.map.rows,
+                        &self.zombie_registry.zombies[id as usize].rows,
+                        Some(lane),
+                    )
+                    .then_some(lane as usize)?,
+                    None => self.random_lane(id)?,
+                };
+                Some(SpawnZombie {
+                    id,
+                    lane,
+                    x: self.rng.random_range((min_x)..=(1. - width)),
+                    y: self.map.pos_to_coord_y(row + 1) - height,
+                })
+            })
+            .collect()
+    }
+
+    pub fn command(&mut self, command: Command) -> bool {
+        match command {
+            Command::PlacePlant { plant, x, y } => self.place_plant(plant, x, y),
+            Command::RemovePlant { x, y } => self.remove_plant(x, y),
+            Command::CollectSun(sun) => {
+                if sun >= self.suns.len() {
+                    return false;
+                }
+                self.suns.remove(sun);
+                self.money += 25;
+                true
+            }
+        }
+    }
+
+    fn place_plant(&mut self, shop_plant: usize, x: usize, y: usize) -> bool {
+        if !self.is_recharged(shop_plant) {
+            return false;
+        }
+        let Some(plant) = self.shop_plants.get(shop_plant) else {
+            return false;
+        };
+        if self.money < plant.cost() {
+            return false;
+        }
+        let Some(tile) = self.plants.get_mut(y).and_then(|row| row.get_mut(x)) else {
+            return false;
+        };
+        let can_place = tile.can_place(plant.definition(), self.map.rows[y]);
+        if can_place {
+            self.money -= plant.cost();
+            self.shop_recharges[shop_plant] = plant.definition().recharge;
+            tile.place(plant.as_ref().clone());
+        }
+        can_place
+    }
+
+    pub(super) fn remove_plant(&mut self, x: usize, y: usize) -> bool {
+        self.plants
+            .get_mut(y)
+            .and_then(|row| row.get_mut(x))
+            .is_some_and(Tile::remove_outer)
+    }
+
+    pub fn is_recharged(&self, shop_plant: usize) -> bool {
+        self.shop_recharges
+            .get(shop_plant)
+            .is_some_and(Duration::is_zero)
+    }
+
+    pub fn recharge_ratio(&self, shop_plant: usize) -> f32 {
+        let (Some(plant), Some(remaining)) = (
+            self.shop_plants.get(shop_plant),
+            self.shop_recharges.get(shop_plant),
+        ) else {
+            return 0.;
+        };
+        let recharge = plant.definition().recharge;
+        if recharge.is_zero() {
+            0.
+        } else {
+            remaining.as_secs_f32() / recharge.as_secs_f32()
+        }
+    }
+
+    pub fn remaining_lawn(&self) -> u8 {
+        ((self.closest_zombie - self.map.left) / self.map.width * 100.).clamp(0., 100.) as u8
+    }
+
+    pub fn update(&mut self, elapsed: Duration) -> Result<()> {
+        if self.end.is_some() {
+            return Ok(());
+        }
+        if !self.zombies.iter().flatten().any(|_| true) && self.spawn_waits.is_empty() {
+            self.end = Some(true);
+            return Ok(());
+        }
+        self.time += elapsed;
+        for recharge in self.shop_recharges.iter_mut() {
+            *recharge = recharge.saturating_sub(elapsed);
+        }
+        for plant in self
+            .plants
+            .iter_mut()
+            .flatten()
+            .flat_map(|tile| tile.layers.iter_mut().flatten())
+        {
+            plant.update(elapsed)?;
+        }
+        self.update_zombies(elapsed)?;
+        if let Some(false) = self.end {
+            return Ok(());
+        }
+        self.update_projectiles(elapsed)?;
+        self.update_suns(elapsed)?;
+        self.spawn_projectiles();
+        self.detonate_plants();
+        self.act_plants();
+        self.update_zombie_wave(elapsed);
+        Ok(())
+    }
+}
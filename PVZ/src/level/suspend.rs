@@ -0,0 +1,270 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::fs;
+
+use crate::{
+    plants::{plant_from_json, registry::PlantRegistry, tile::Tile},
+    projectile::projectile_from_json,
+    sun::Sun,
+    zombie::{registry::ZombieRegistry, zombie_from_json},
+};
+
+use super::{
+    config::{fits_lane, write_json, Map},
+    parse::{duration_value, f32_value, read_json, Field, LoadError},
+    simulation::{SimRng, Simulation, SpawnZombie},
+    Level,
+};
+
+pub const SUSPEND_FILE: &str = "suspend.json";
+const SUSPEND_VERSION: u64 = 18;
+
+impl Level {
+    pub fn suspend(&mut self) -> Result<()> {
+        let json = json!({
+            "version": SUSPEND_VERSION,
+            "level": self.id,
+            "sim": self.sim.save_state(),
+        });
+        write_json(SUSPEND_FILE.to_owned(), &json)
+    }
+
+    pub fn resume(
+        zombie_registry: &'static ZombieRegistry,
+        plant_registry: &'static PlantRegistry,
+    ) -> Result<Self, LoadError> {
+        let json = read_json(SUSPEND_FILE)?;
+        let json = Field::root(SUSPEND_FILE, &json);
+        let version = json.get("version");
+        if version.u64()? != SUSPEND_VERSION {
+            remove_suspend_file()?;
+            return Err(version.invalid(
+                "a game suspended by the current version (the outdated save was discarded)",
+            ));
+        }
+        let mut level = Level::new(
+            json.get("level").u8()?,
+            Simulation::load_state(&json.get("sim"), zombie_registry, plant_registry)?,
+        );
+        level.resumed = true;
+        remove_suspend_file()?;
+        Ok(level)
+    }
+}
+
+fn remove_suspend_file() -> Result<(), LoadError> {
+    fs::remove_file(SUSPEND_FILE).map_err(|error| LoadError::Io {
+        file: SUSPEND_FILE.to_owned(),
+        error,
+    })
+}
+
+impl Simulation {
+    pub fn save_state(&self) -> Value {
+        let mut json = json!({
+            "map": self.map.id,
+            "money": self.money,
+            "time_ns": duration_value(self.time),
+            "closest_zombie": f32_value(self.closest_zombie),
+            "next_zombie_uid": self.next_zombie_uid,
+            "next_sun_ns": duration_value(self.next_sun),
+            "seed": self.seed,
+            "rng_draws": self.rng.draws(),
+            "shop": self
+                .shop_plants
+                .iter()
+                .zip(self.shop_recharges.iter())
+                .map(|(plant, recharge)| {
+                    json!({
+                        "type": plant.definition().name,
+                        "recharge_ns": duration_value(*recharge),
+                    })
+                })
+                .collect::<Vec<Value>>(),
+            "plants": self
+                .plants
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|tile| tile.plants().map(|plant| plant.save()).collect())
+                        .collect()
+                })
+                .collect::<Vec<Vec<Vec<Value>>>>(),
+            "zombies": self
+                .zombies
+                .iter()
+                .map(|row| row.iter().map(|zombie| zombie.save()).collect())
+                .collect::<Vec<Vec<Value>>>(),
+            "projectiles": self
+                .projectiles
+                .iter()
+                .map(|row| row.iter().map(|projectile| projectile.save()).collect())
+                .collect::<Vec<Vec<Value>>>(),
+            "suns": self.suns.iter().map(Sun::save).collect::<Vec<Value>>(),
+            "waves": self
+                .spawn_waits
+                .iter()
+                .zip(self.spawn_zombies.iter())
+                .map(|(wait, zombies)| {
+                    json!({
+                        "wait_ns": duration_value(*wait),
+                        "zombies": zombies
+                            .iter()
+                            .map(|zombie| {
+                                let mut json = json!({
+                                    "type": self.zombie_registry.name(zombie.id),
+                                    "x": f32_value(zombie.x),
+                                    "y": f32_value(zombie.y),
+                                });
+                                if let Some(lane) = zombie.lane {
+                                    json["lane"] = lane.into();
+                                }
+                                json
+                            })
+                            .collect::<Vec<Value>>(),
+                    })
+                })
+                .collect::<Vec<Value>>(),
+        });
+        if let Some(level_seed) = self.level_seed {
+            json["level_seed"] = level_seed.into();
+        }
+        json
+    }
+
+    pub fn load_state(
+        json: &Field,
+        zombie_registry: &'static ZombieRegistry,
+        plant_registry: &'static PlantRegistry,
+    ) -> Result<Self, LoadError> {
+        let map = Map::load(json.get("map").u8()?)?;
+        let (rows, cols) = (map.rows.len(), map.cols as usize);
+        let waves = json.get("waves").items()?;
+        let shop = json.get("shop").items()?;
+        Ok(Simulation {
+            plants: grid(&json.get("plants"), rows, Some(cols), |tile| {
+                let mut cell = Tile::default();
+                for plant in tile.items()? {
+                    let plant = plant_from_json(&plant, plant_registry)?;
+                    if cell.layers[plant.definition().layer as usize].is_some() {
+                        return Err(tile.invalid("at most one plant per layer"));
+                    }
+                    cell.place(plant);
+                }
+                Ok(cell)
+            })?,
+            zombies: grid(&json.get("zombies"), rows, None, |zombie| {
+                zombie_from_json(zombie, zombie_registry)
+            })?,
+            projectiles: grid(&json.get("projectiles"), rows, None, |projectile| {
+                projectile_from_json(projectile, plant_registry)
+            })?,
+            suns: json
+                .get("suns")
+                .items()?
+                .iter()
+                .map(Sun::load)
+                .collect::<Result<_, LoadError>>()?,
+            next_sun: json.get("next_sun_ns").duration()?,
+            shop_plants: shop
+                .iter()
+                .map(|plant| {
+                    let plant_type = plant.get("type");
+                    plant_registry
+                        .id(plant_type.str()?)
+                        .map(|id| plant_registry.spawn(id))
+                        .ok_or_else(|| plant_type.invalid("a plant type"))
+                })
+                .collect::<Result<_, LoadError>>()?,
+            shop_recharges: shop
+                .iter()
+                .map(|plant| plant.get("recharge_ns").duration())
+                .collect::<Result<_, LoadError>>()?,
+            spawn_waits: waves
+                .iter()
+                .map(|wave| wave.get("wait_ns").duration())
+                .collect::<Result<_, LoadError>>()?,
+            spawn_zombies: waves
+                .iter()
+                .map(|wave| {
+                    wave.get("zombies")
+                        .items()?
+                        .iter()
+                        .map(|zombie| spawn_zombie(zombie, &map, zombie_registry))
+                        .collect()
+                })
+                .collect::<Result<_, LoadError>>()?,
+            money: json.get("money").u32()?,
+            time: json.get("time_ns").duration()?,
+            closest_zombie: json.get("closest_zombie").f32()?,
+            next_zombie_uid: json.get("next_zombie_uid").u64()?,
+            level_seed: json
+                .get("level_seed")
+                .optional()
+                .map(|s| s.u64())
+                .transpose()?,
+            rng: SimRng::new(json.get("seed").u64()?, json.get("rng_draws").u64()?),
+            ..Simulation::new(
+                zombie_registry,
+                plant_registry,
+                map,
+                0,
+                &[],
+                Vec::new(),
+                Vec::new(),
+                json.get("seed").u64()?,
+            )
+            .ok_or_else(|| {
+                json.get("waves")
+                    .invalid("zombies that can move in the map")
+            })?
+        })
+    }
+}
+
+fn spawn_zombie(
+    json: &Field,
+    map: &Map,
+    zombie_registry: &'static ZombieRegistry,
+) -> Result<SpawnZombie, LoadError> {
+    let zombie_type = json.get("type");
+    let id = zombie_registry
+        .id(zombie_type.str()?)
+        .ok_or_else(|| zombie_type.invalid("a zombie type"))?;
+    let lane_field = json.get("lane");
+    let lane = lane_field.optional().map(|l| l.u8()).transpose()?;
+    if !fits_lane(&map.rows, &zombie_registry.zombies[id as usize].rows, lane) {
+        return Err(match lane {
+            Some(_) => lane_field.invalid("a lane inside the map the zombie can move in"),
+            None => zombie_type.invalid("a zombie type that can move in the map"),
+        });
+    }
+    Ok(SpawnZombie {
+        id,
+        lane,
+        x: json.get("x").f32()?,
+        y: json.get("y").f32()?,
+    })
+}
+
+fn grid<T>(
+    json: &Field,
+    rows: usize,
+    cols: Option<usize>,
+    item: impl Fn(&Field) -> Result<T, LoadError>,
+) -> Result<Vec<Vec<T>>, LoadError> {
+    let fields = json.items()?;
+    if fields.len() != rows {
+        return Err(json.invalid("one entry per map row"));
+    }
+    fields
+        .iter()
+        .map(|row| {
+            let items = row.items()?;
+            if cols.is_some_and(|cols| cols != items.len()) {
+                return Err(row.invalid("one entry per map column"));
+            }
+            items.iter().map(&item).collect()
+        })
+        .collect()
+}
@@ -0,0 +1,1410 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::{fs, path::Path, time::Duration};
+
+use crate::{
+    plants::{
+        registry::{PlantDefinition, PlantKind, PlantRegistry},
+        tile::Tile,
+    },
+    projectile::{DamageType, Lobbed, Pea, Piercing, Star},
+    zombie::{
+        registry::{ZombieArmor, ZombieDefinition, ZombieRegistry, ZombieStage},
+        Hit, HitSource, Zombie,
+    },
+};
+
+use super::{
+    config::{LevelFile, Map, RowType},
+    parse::{Field, LoadError},
+    simulation::{Command, Simulation},
+    suspend::SUSPEND_FILE,
+};
+
+const STEP: Duration = Duration::from_millis(10);
+
+type Wave<'a> = (u64, &'a [&'a str]);
+type Snapshot = (Vec<(usize, f32)>, Vec<(f32, f32, f32)>);
+
+fn registries() -> Result<(&'static ZombieRegistry, &'static PlantRegistry)> {
+    Ok((
+        Box::leak(Box::new(ZombieRegistry::load()?)),
+        Box::leak(Box::new(PlantRegistry::load()?)),
+    ))
+}
+
+fn simulation(map: u8, money: u32, plants: &[&str], waves: &[Wave]) -> Result<Simulation> {
+    let (zombie_registry, plant_registry) = registries()?;
+    let shop_plants = plants
+        .iter()
+        .map(|name| {
+            plant_registry
+                .id(name)
+                .ok_or_else(|| anyhow!("unknown plant {name}"))
+        })
+        .collect::<Result<Vec<u8>>>()?;
+    let spawn_zombies = waves
+        .iter()
+        .map(|(_, zombies)| {
+            zombies
+                .iter()
+                .map(|&name| {
+                    zombie_registry
+                        .id(name)
+                        .map(|id| (id, None))
+                        .ok_or_else(|| anyhow!("unknown zombie {name}"))
+                })
+                .collect()
+        })
+        .collect::<Result<Vec<Vec<(u8, Option<u8>)>>>>()?;
+    Simulation::new(
+        zombie_registry,
+        plant_registry,
+        Map::load(map)?,
+        money,
+        &shop_plants,
+        waves
+            .iter()
+            .map(|&(wait_ms, _)| Duration::from_millis(wait_ms))
+            .collect(),
+        spawn_zombies,
+        0,
+    )
+    .ok_or_else(|| anyhow!("a zombie cannot move in map {map}"))
+}
+
+fn run(sim: &mut Simulation, duration: Duration) -> Result<()> {
+    let mut time = Duration::ZERO;
+    while time < duration && sim.end.is_none() {
+        sim.update(STEP)?;
+        time += STEP;
+    }
+    Ok(())
+}
+
+fn add_zombie(sim: &mut Simulation, name: &str, row: usize, col: usize) -> Result<u64> {
+    let id = sim
+        .zombie_registry
+        .id(name)
+        .ok_or_else(|| anyhow!("unknown zombie {name}"))?;
+    let uid = sim.next_zombie_uid;
+    let mut zombie = sim.zombie_registry.spawn(id);
+    zombie.set_x(sim.map.pos_to_coord_x(col));
+    zombie.set_uid(uid);
+    sim.next_zombie_uid += 1;
+    sim.zombies[row].push(zombie);
+    Ok(uid)
+}
+
+fn health(zombie: &dyn Zombie) -> u64 {
+    zombie.save()["health"].as_u64().unwrap_or_default()
+}
+
+fn zombie_definition(
+    registry: &'static ZombieRegistry,
+    name: &str,
+) -> Result<&'static ZombieDefinition> {
+    registry
+        .id(name)
+        .and_then(|id| registry.get(id))
+        .ok_or_else(|| anyhow!("unknown zombie {name}"))
+}
+
+fn armor(zombie: &dyn Zombie) -> Vec<u64> {
+    zombie.save()["armor"]
+        .as_array()
+        .map(|armor| armor.iter().filter_map(Value::as_u64).collect())
+        .unwrap_or_default()
+}
+
+fn plant_definition(sim: &Simulation, name: &str) -> Result<&'static PlantDefinition> {
+    sim.plant_registry
+        .id(name)
+        .and_then(|id| sim.plant_registry.get(id))
+        .ok_or_else(|| anyhow!("unknown plant {name}"))
+}
+
+struct TempFiles(&'static [&'static str]);
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for file in self.0 {
+            if Path::new(file).exists() {
+                let _ = fs::remove_file(file);
+            }
+        }
+    }
+}
+
+fn write_json(file: &str, json: Value) -> Result<()> {
+    fs::write(file, json.to_string()).map_err(|e| anyhow!(e))
+}
+
+fn layers(tile: &Tile) -> Vec<&str> {
+    tile.plants()
+        .map(|plant| plant.definition().name.as_str())
+        .collect()
+}
+
+fn invalid_field(error: LoadError) -> Result<(String, String)> {
+    match error {
+        LoadError::InvalidField { file, field, .. } => Ok((file, field)),
+        error => Err(anyhow!("expected an invalid field, found {error}")),
+    }
+}
+
+fn snapshot(sim: &Simulation) -> Snapshot {
+    (
+        sim.zombies
+            .iter()
+            .enumerate()
+            .flat_map(|(row, zombies)| zombies.iter().map(move |z| (row, z.rect(0.).x())))
+            .collect(),
+        sim.suns
+            .iter()
+            .map(|sun| (sun.x, sun.y, sun.dist))
+            .collect(),
+    )
+}
+
+#[test]
+fn update_counts_down_to_the_next_wave() -> Result<()> {
+    let mut sim = simulation(1, 0, &[], &[(60_000, &["normal"])])?;
+    sim.update(Duration::from_millis(250))?;
+    sim.update(Duration::from_millis(250))?;
+    assert_eq!(sim.spawn_waits, vec![Duration::from_millis(59_500)]);
+    Ok(())
+}
+
+#[test]
+fn place_plant_spends_money() -> Result<()> {
+    let mut sim = simulation(1, 150, &["peashooter"], &[])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 2,
+        y: 1
+    }));
+    assert_eq!(sim.money, 50);
+    assert!(!layers(&sim.plants[1][2]).is_empty());
+    assert!(!sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 1
+    }));
+    assert!(!sim.command(Command::PlacePlant {
+        plant: 1,
+        x: 3,
+        y: 1
+    }));
+    sim.money = 150;
+    assert!(!sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 20,
+        y: 1
+    }));
+    assert_eq!(sim.money, 150);
+    Ok(())
+}
+
+#[test]
+fn collect_sun_adds_money() -> Result<()> {
+    let mut sim = simulation(1, 0, &[], &[(60_000, &["normal"])])?;
+    assert!(!sim.command(Command::CollectSun(0)));
+    run(&mut sim, Duration::from_secs(6))?;
+    assert_eq!(sim.suns.len(), 1);
+    assert!(sim.command(Command::CollectSun(0)));
+    assert_eq!(sim.money, 25);
+    assert!(sim.suns.is_empty());
+    Ok(())
+}
+
+#[test]
+fn placing_a_plant_waits_for_its_recharge() -> Result<()> {
+    let mut sim = simulation(1, 1000, &["peashooter"], &[(60_000, &["normal"])])?;
+    let place = |x| Command::PlacePlant { plant: 0, x, y: 0 };
+    assert!(sim.command(place(0)));
+    assert!(!sim.is_recharged(0));
+    assert!(!sim.command(place(1)));
+    assert_eq!(sim.money, 900);
+    run(&mut sim, Duration::from_millis(7000))?;
+    assert!(!sim.command(place(1)));
+    assert!(sim.recharge_ratio(0) > 0.);
+    assert_eq!(sim.recharge_ratio(5), 0.);
+    run(&mut sim, Duration::from_millis(500))?;
+    assert!(sim.is_recharged(0));
+    assert!(sim.command(place(1)));
+    assert_eq!(sim.money, 800);
+    Ok(())
+}
+
+#[test]
+fn level_is_won_once_every_wave_is_cleared() -> Result<()> {
+    let mut sim = simulation(1, 0, &[], &[])?;
+    sim.update(STEP)?;
+    assert_eq!(sim.end, Some(true));
+    Ok(())
+}
+
+#[test]
+fn level_is_lost_when_a_zombie_reaches_the_house() -> Result<()> {
+    let mut sim = simulation(0, 0, &[], &[(0, &["normal"])])?;
+    run(&mut sim, Duration::from_secs(120))?;
+    assert_eq!(sim.end, Some(false));
+    Ok(())
+}
+
+#[test]
+fn same_seed_gives_the_same_run() -> Result<()> {
+    let waves: &[Wave] = &[
+        (0, &["normal", "cone", "normal", "normal", "cone", "normal"]),
+        (3000, &["normal", "normal", "cone"]),
+    ];
+    let mut first = simulation(2, 0, &[], waves)?;
+    let mut second = simulation(2, 0, &[], waves)?;
+    for _ in 0..4 {
+        run(&mut first, Duration::from_secs(3))?;
+        run(&mut second, Duration::from_secs(3))?;
+        assert_eq!(snapshot(&first), snapshot(&second));
+    }
+    let (zombies, suns) = snapshot(&first);
+    assert_eq!(zombies.len(), 9);
+    assert_eq!(suns.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn saving_does_not_change_the_run() -> Result<()> {
+    let waves: &[Wave] = &[
+        (0, &["normal", "cone", "normal"]),
+        (3000, &["normal", "cone"]),
+    ];
+    let mut saved = simulation(2, 0, &[], waves)?;
+    let mut unsaved = simulation(2, 0, &[], waves)?;
+    for _ in 0..4 {
+        saved.save_state();
+        run(&mut saved, Duration::from_secs(3))?;
+        run(&mut unsaved, Duration::from_secs(3))?;
+        assert_eq!(snapshot(&saved), snapshot(&unsaved));
+    }
+    assert_eq!(saved.save_state(), unsaved.save_state());
+    Ok(())
+}
+
+#[test]
+fn legacy_map_converts_to_json() -> Result<()> {
+    let _files = TempFiles(&["assets/maps/200.data", "assets/maps/200.json"]);
+    let mut data = Vec::new();
+    for value in [0.29f32, 0.23, 0.69, 0.52] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend([3, 9, 0, 1, 0]);
+    fs::write("assets/maps/200.data", data).map_err(|e| anyhow!(e))?;
+
+    let legacy = Map::load_legacy(200)?;
+    legacy.save()?;
+    assert!(!Path::new("assets/maps/200.data").exists());
+    let map = Map::load(200)?;
+
+    assert_eq!(
+        (map.top, map.left, map.width, map.height),
+        (0.29, 0.23, 0.69, 0.52)
+    );
+    assert_eq!(
+        (map.top, map.left, map.width, map.height),
+        (legacy.top, legacy.left, legacy.width, legacy.height)
+    );
+    assert!(map.rows == [RowType::Grass, RowType::Water, RowType::Grass]);
+    assert!(map.rows == legacy.rows);
+    assert_eq!(map.cols, 9);
+    Ok(())
+}
+
+#[test]
+fn legacy_level_converts_to_json() -> Result<()> {
+    let _files = TempFiles(&["levels/200.data", "levels/200.json"]);
+    let mut data = vec![1];
+    data.extend(75u32.to_le_bytes());
+    data.extend([2, 20, 5, 2, 0, 3, 1, 1, 1, 0, 2]);
+    data.extend(42u64.to_le_bytes());
+    fs::write("levels/200.data", data).map_err(|e| anyhow!(e))?;
+
+    let (zombie_registry, plant_registry) = registries()?;
+    let legacy = LevelFile::load_legacy(200, zombie_registry, plant_registry)?;
+    legacy.save(200, zombie_registry, plant_registry)?;
+    assert!(!Path::new("levels/200.data").exists());
+    let level = LevelFile::load(200, zombie_registry, plant_registry)?;
+
+    assert_eq!((level.map, level.money, level.seed), (1, 75, Some(42)));
+    assert_eq!(
+        (level.map, level.money, level.seed),
+        (legacy.map, legacy.money, legacy.seed)
+    );
+    assert_eq!(
+        level.spawn_waits,
+        vec![Duration::from_secs(20), Duration::from_secs(5)]
+    );
+    assert_eq!(level.spawn_waits, legacy.spawn_waits);
+    assert_eq!(
+        level.spawn_zombies,
+        vec![
+            vec![(0, None), (0, None), (0, None), (1, None)],
+            vec![(0, None), (0, None)]
+        ]
+    );
+    assert_eq!(level.spawn_zombies, legacy.spawn_zombies);
+    assert_eq!(level.plants, legacy.plants);
+    assert_eq!(level.plants.len(), 6);
+    Ok(())
+}
+
+#[test]
+fn truncated_legacy_map_reports_the_missing_bytes() -> Result<()> {
+    let _files = TempFiles(&["assets/maps/201.data"]);
+    fs::write("assets/maps/201.data", [0; 10]).map_err(|e| anyhow!(e))?;
+    let Err(error) = Map::load(201) else {
+        return Err(anyhow!("a truncated map should not load"));
+    };
+    let LoadError::UnexpectedEnd {
+        file,
+        offset,
+        expected,
+    } = error
+    else {
+        return Err(anyhow!("expected an unexpected end, found {error}"));
+    };
+    assert_eq!(file, "assets/maps/201.data");
+    assert_eq!((offset, expected), (8, "the map width"));
+    Ok(())
+}
+
+#[test]
+fn unknown_row_type_reports_its_field() -> Result<()> {
+    let _files = TempFiles(&["assets/maps/202.json"]);
+    write_json(
+        "assets/maps/202.json",
+        json!({
+            "version": 1,
+            "top": 0.3,
+            "left": 0.2,
+            "width": 0.7,
+            "height": 0.5,
+            "cols": 9,
+            "rows": ["grass", "lava"],
+        }),
+    )?;
+    let Err(error) = Map::load(202) else {
+        return Err(anyhow!("a map with an unknown row should not load"));
+    };
+    assert_eq!(
+        invalid_field(error)?,
+        ("assets/maps/202.json".to_owned(), "rows[1]".to_owned())
+    );
+    Ok(())
+}
+
+#[test]
+fn unknown_zombie_type_reports_its_field() -> Result<()> {
+    let (zombie_registry, plant_registry) = registries()?;
+    let _files = TempFiles(&["levels/203.json"]);
+    write_json(
+        "levels/203.json",
+        json!({
+            "version": 1,
+            "map": 1,
+            "money": 50,
+            "waves": [
+                { "wait_ms": 1000, "zombies": [{ "type": "normal", "count": 1 }] },
+                { "wait_ms": 1000, "zombies": [{ "type": "dragon", "count": 1 }] },
+            ],
+        }),
+    )?;
+    let Err(error) = LevelFile::load(203, zombie_registry, plant_registry) else {
+        return Err(anyhow!("a level with an unknown zombie should not load"));
+    };
+    assert_eq!(
+        invalid_field(error)?,
+        (
+            "levels/203.json".to_owned(),
+            "waves[1].zombies[0].type".to_owned()
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn lane_outside_the_map_reports_its_field() -> Result<()> {
+    let (zombie_registry, plant_registry) = registries()?;
+    let _files = TempFiles(&["levels/204.json"]);
+    write_json(
+        "levels/204.json",
+        json!({
+            "version": 1,
+            "map": 1,
+            "money": 50,
+            "waves": [{ "wait_ms": 1000, "zombies": [{ "type": "normal", "count": 1, "lane": 3 }] }],
+        }),
+    )?;
+    let Err(error) = Simulation::load(204, None, zombie_registry, plant_registry) else {
+        return Err(anyhow!(
+            "a level with a lane outside the map should not load"
+        ));
+    };
+    assert_eq!(
+        invalid_field(error)?,
+        (
+            "levels/204.json".to_owned(),
+            "waves[0].zombies[0].lane".to_owned()
+        )
+    );
+
+    write_json(
+        "levels/204.json",
+        json!({
+            "version": 1,
+            "map": 1,
+            "money": 50,
+            "waves": [{ "wait_ms": 1000, "zombies": [
+                { "type": "normal", "count": 1, "lane": 2 },
+                { "type": "swimmer", "count": 1 },
+            ] }],
+        }),
+    )?;
+    let Err(error) = Simulation::load(204, None, zombie_registry, plant_registry) else {
+        return Err(anyhow!(
+            "a level with a swimmer on a dry map should not load"
+        ));
+    };
+    assert_eq!(
+        invalid_field(error)?,
+        (
+            "levels/204.json".to_owned(),
+            "waves[0].zombies[1].type".to_owned()
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn wrong_version_reports_its_field() -> Result<()> {
+    let (zombie_registry, plant_registry) = registries()?;
+    let _files = TempFiles(&["levels/205.json"]);
+    write_json(
+        "levels/205.json",
+        json!({ "version": 2, "map": 1, "money": 50, "waves": [] }),
+    )?;
+    let Err(error) = LevelFile::load(205, zombie_registry, plant_registry) else {
+        return Err(anyhow!("a level from another version should not load"));
+    };
+    assert_eq!(
+        invalid_field(error)?,
+        ("levels/205.json".to_owned(), "version".to_owned())
+    );
+    Ok(())
+}
+
+#[test]
+fn suspended_state_resumes_the_same_run() -> Result<()> {
+    let mut sim = simulation(
+        2,
+        300,
+        &["peashooter"],
+        &[
+            (0, &["normal", "cone", "normal", "normal", "cone"]),
+            (20_000, &["normal"]),
+        ],
+    )?;
+    for y in 0..3 {
+        sim.shop_recharges[0] = Duration::ZERO;
+        assert!(sim.command(Command::PlacePlant { plant: 0, x: 0, y }));
+    }
+    run(&mut sim, Duration::from_secs(12))?;
+    assert!(sim.zombies.iter().flatten().count() > 0);
+    assert!(sim.projectiles.iter().flatten().count() > 0);
+
+    let state = sim.save_state();
+    let mut resumed = Simulation::load_state(
+        &Field::root(SUSPEND_FILE, &state),
+        sim.zombie_registry,
+        sim.plant_registry,
+    )?;
+    for _ in 0..4 {
+        run(&mut sim, Duration::from_secs(5))?;
+        run(&mut resumed, Duration::from_secs(5))?;
+        assert_eq!(snapshot(&sim), snapshot(&resumed));
+    }
+    assert_eq!(sim.save_state(), resumed.save_state());
+    Ok(())
+}
+
+#[test]
+fn registry_spawns_zombies_by_name() -> Result<()> {
+    let (registry, _) = registries()?;
+    for name in ["normal", "cone"] {
+        let id = registry
+            .id(name)
+            .ok_or_else(|| anyhow!("unknown zombie {name}"))?;
+        assert_eq!(registry.name(id), Some(name));
+        let definition = registry
+            .get(id)
+            .ok_or_else(|| anyhow!("no definition for {name}"))?;
+        let zombie = registry.spawn(id).save();
+        assert_eq!(zombie["type"], name);
+        assert_eq!(zombie["health"], definition.max_health());
+    }
+    assert_eq!(registry.id("dragon"), None);
+    Ok(())
+}
+
+#[test]
+fn zombie_appearance_follows_its_armor_then_its_health() -> Result<()> {
+    let (registry, _) = registries()?;
+    let cone = zombie_definition(registry, "cone")?;
+    let thresholds = cone
+        .stages
+        .iter()
+        .map(|stage| stage.health)
+        .collect::<Vec<usize>>();
+    assert_eq!(thresholds, vec![100, 200]);
+    assert_eq!(cone.appearance(200, &[440], false, false).0, 3);
+    assert_eq!(cone.appearance(200, &[221], false, false).0, 3);
+    assert_eq!(cone.appearance(200, &[220], false, false).0, 2);
+    assert_eq!(cone.appearance(200, &[0], false, false).0, 1);
+    assert_eq!(cone.appearance(150, &[0], false, false).0, 1);
+    assert_eq!(cone.appearance(1, &[0], false, false).0, 0);
+    Ok(())
+}
+
+#[test]
+fn armor_is_damaged_before_the_body() -> Result<()> {
+    let (registry, _) = registries()?;
+    let mut zombie = zombie_definition(registry, "cone")?.spawn();
+    assert_eq!(
+        zombie.hit(Hit::new(200, DamageType::Normal, HitSource::Front)),
+        (false, false)
+    );
+    assert_eq!(
+        (armor(zombie.as_ref()), health(zombie.as_ref())),
+        (vec![240], 200)
+    );
+    assert_eq!(
+        zombie.hit(Hit::new(300, DamageType::Normal, HitSource::Front)),
+        (false, false)
+    );
+    assert_eq!(
+        (armor(zombie.as_ref()), health(zombie.as_ref())),
+        (vec![0], 140)
+    );
+    assert_eq!(
+        zombie.hit(Hit::new(140, DamageType::Normal, HitSource::Front)),
+        (true, false)
+    );
+    Ok(())
+}
+
+#[test]
+fn armor_without_overflow_drops_the_rest_of_the_hit() -> Result<()> {
+    let stage = |health| ZombieStage {
+        health,
+        height: 0.,
+        texture: String::new(),
+        frozen_texture: None,
+    };
+    let definition: &'static ZombieDefinition = Box::leak(Box::new(ZombieDefinition {
+        id: 0,
+        name: "newspaper".to_owned(),
+        speed: 0.,
+        frozen_speed: 0.5,
+        eat_rate: 0.,
+        width: 0.,
+        hitbox_x: 0.,
+        hitbox_width: 0.,
+        stages: vec![stage(100), stage(200)],
+        armor: vec![ZombieArmor {
+            name: "newspaper".to_owned(),
+            stages: vec![stage(50)],
+            blocks_freeze: false,
+            overflow: false,
+            shield: false,
+            flying: false,
+            rage: None,
+        }],
+        vault: None,
+        submerged: None,
+        rows: vec![RowType::Grass],
+    }));
+    let mut zombie = definition.spawn();
+    assert_eq!(
+        zombie.hit(Hit::new(180, DamageType::Normal, HitSource::Front)),
+        (false, false)
+    );
+    assert_eq!(
+        (armor(zombie.as_ref()), health(zombie.as_ref())),
+        (vec![0], 200)
+    );
+    assert_eq!(
+        zombie.hit(Hit::new(180, DamageType::Normal, HitSource::Front)),
+        (false, false)
+    );
+    assert_eq!(health(zombie.as_ref()), 20);
+    Ok(())
+}
+
+#[test]
+fn screen_door_blocks_freezing_until_it_breaks() -> Result<()> {
+    let (registry, _) = registries()?;
+    let mut zombie = zombie_definition(registry, "screen_door")?.spawn();
+    zombie.hit(Hit::new(20, DamageType::Ice, HitSource::Front));
+    assert!(!zombie.freezed());
+    assert_eq!(armor(zombie.as_ref()), vec![1080]);
+    zombie.hit(Hit::new(1080, DamageType::Normal, HitSource::Front));
+    zombie.hit(Hit::new(20, DamageType::Ice, HitSource::Front));
+    assert!(zombie.freezed());
+    assert_eq!(health(zombie.as_ref()), 180);
+    Ok(())
+}
+
+#[test]
+fn level_shop_offers_its_plant_roster() -> Result<()> {
+    let _files = TempFiles(&["levels/206.json", "levels/207.json"]);
+    let (zombie_registry, plant_registry) = registries()?;
+    let level = |plants: Option<Value>| {
+        let mut level = json!({
+            "version": 1,
+            "map": 1,
+            "money": 50,
+            "waves": [{ "wait_ms": 1000, "zombies": [{ "type": "normal", "count": 1 }] }],
+        });
+        if let Some(plants) = plants {
+            level["plants"] = plants;
+        }
+        level
+    };
+    write_json(
+        "levels/206.json",
+        level(Some(json!(["sunflower", "peashooter"]))),
+    )?;
+    write_json("levels/207.json", level(None))?;
+
+    let sim = Simulation::load(206, Some(0), zombie_registry, plant_registry)?;
+    let shop = sim
+        .shop_plants
+        .iter()
+        .map(|plant| plant.definition().name.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(shop, vec!["sunflower", "peashooter"]);
+
+    let every_plant = LevelFile::load(207, zombie_registry, plant_registry)?;
+    assert_eq!(every_plant.plants, plant_registry.ids());
+    Ok(())
+}
+
+#[test]
+fn unknown_plant_reports_its_field() -> Result<()> {
+    let _files = TempFiles(&["levels/208.json"]);
+    let (zombie_registry, plant_registry) = registries()?;
+    write_json(
+        "levels/208.json",
+        json!({
+            "version": 1,
+            "map": 1,
+            "money": 50,
+            "plants": ["peashooter", "cactus"],
+            "waves": [],
+        }),
+    )?;
+    let Err(error) = LevelFile::load(208, zombie_registry, plant_registry) else {
+        return Err(anyhow!("a level with an unknown plant should not load"));
+    };
+    assert_eq!(
+        invalid_field(error)?,
+        ("levels/208.json".to_owned(), "plants[1]".to_owned())
+    );
+    Ok(())
+}
+
+fn place(sim: &mut Simulation, plant: usize, x: usize, y: usize) -> bool {
+    sim.shop_recharges.fill(Duration::ZERO);
+    sim.command(Command::PlacePlant { plant, x, y })
+}
+
+#[test]
+fn nenuphar_only_floats_on_water() -> Result<()> {
+    let mut sim = simulation(3, 1000, &["nenuphar"], &[])?;
+    assert!(!place(&mut sim, 0, 2, 0));
+    assert!(place(&mut sim, 0, 2, 2));
+    assert!(!place(&mut sim, 0, 2, 2));
+    assert_eq!(layers(&sim.plants[2][2]), vec!["nenuphar"]);
+    Ok(())
+}
+
+#[test]
+fn main_plant_needs_a_base_on_water() -> Result<()> {
+    let mut sim = simulation(3, 1000, &["nenuphar", "peashooter"], &[])?;
+    assert!(!place(&mut sim, 1, 2, 2));
+    assert!(place(&mut sim, 0, 2, 2));
+    assert!(place(&mut sim, 1, 2, 2));
+    assert!(!place(&mut sim, 1, 2, 2));
+    assert!(place(&mut sim, 1, 2, 0));
+    assert_eq!(layers(&sim.plants[2][2]), vec!["nenuphar", "peashooter"]);
+    assert_eq!(layers(&sim.plants[0][2]), vec!["peashooter"]);
+    Ok(())
+}
+
+#[test]
+fn armor_goes_over_the_main_plant() -> Result<()> {
+    let mut sim = simulation(3, 1000, &["peashooter", "pumpkin"], &[])?;
+    assert!(place(&mut sim, 0, 2, 0));
+    assert!(place(&mut sim, 1, 2, 0));
+    assert!(!place(&mut sim, 1, 2, 0));
+    assert!(!place(&mut sim, 0, 2, 0));
+    assert!(place(&mut sim, 1, 3, 0));
+    assert!(place(&mut sim, 0, 3, 0));
+    assert_eq!(layers(&sim.plants[0][2]), vec!["peashooter", "pumpkin"]);
+    assert_eq!(layers(&sim.plants[0][3]), vec!["peashooter", "pumpkin"]);
+    Ok(())
+}
+
+#[test]
+fn shovel_removes_the_outermost_layer_first() -> Result<()> {
+    let mut sim = simulation(3, 1000, &["nenuphar", "peashooter", "pumpkin"], &[])?;
+    assert!(place(&mut sim, 0, 2, 2));
+    assert!(place(&mut sim, 1, 2, 2));
+    assert!(place(&mut sim, 2, 2, 2));
+
+    assert!(sim.command(Command::RemovePlant { x: 2, y: 2 }));
+    assert_eq!(layers(&sim.plants[2][2]), vec!["nenuphar", "peashooter"]);
+    assert!(sim.command(Command::RemovePlant { x: 2, y: 2 }));
+    assert_eq!(layers(&sim.plants[2][2]), vec!["nenuphar"]);
+    assert!(sim.command(Command::RemovePlant { x: 2, y: 2 }));
+    assert!(layers(&sim.plants[2][2]).is_empty());
+    assert!(!sim.command(Command::RemovePlant { x: 2, y: 2 }));
+    assert!(!sim.command(Command::RemovePlant { x: 20, y: 0 }));
+    Ok(())
+}
+
+#[test]
+fn zombies_eat_the_outermost_layer_first() -> Result<()> {
+    let mut sim = simulation(3, 1000, &["nenuphar", "peashooter", "pumpkin"], &[])?;
+    assert!(place(&mut sim, 0, 2, 2));
+    assert!(place(&mut sim, 1, 2, 2));
+    assert!(place(&mut sim, 2, 2, 2));
+    for plant in sim.plants[2][2].layers.iter_mut().flatten() {
+        *plant.health() = Duration::from_secs(1);
+    }
+    add_zombie(&mut sim, "normal", 2, 3)?;
+    let mut eaten = vec![layers(&sim.plants[2][2]).join(",")];
+    for _ in 0..200 {
+        run(&mut sim, Duration::from_millis(100))?;
+        let tile = layers(&sim.plants[2][2]).join(",");
+        if eaten.last() != Some(&tile) {
+            eaten.push(tile);
+        }
+    }
+    assert_eq!(
+        eaten,
+        vec![
+            "nenuphar,peashooter,pumpkin",
+            "nenuphar,peashooter",
+            "nenuphar",
+            ""
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn wall_holds_zombies_back_longer_than_other_plants() -> Result<()> {
+    let mut sim = simulation(1, 100, &["wall", "sunflower"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 0
+    }));
+    assert!(sim.command(Command::PlacePlant {
+        plant: 1,
+        x: 3,
+        y: 2
+    }));
+    add_zombie(&mut sim, "normal", 0, 4)?;
+    add_zombie(&mut sim, "normal", 2, 4)?;
+    run(&mut sim, Duration::from_secs(15))?;
+
+    assert!(layers(&sim.plants[2][3]).is_empty());
+    let wall = sim.plants[0][3]
+        .plants()
+        .next()
+        .ok_or_else(|| anyhow!("the wall should still stand"))?;
+    let health = wall.save()["health_ns"].as_u64().unwrap_or_default();
+    assert!(health > 0);
+    assert!(health < wall.definition().health.as_nanos() as u64);
+    assert!(sim.zombies[0][0].rect(0.).x() > sim.map.pos_to_coord_x(3));
+    assert!(sim.zombies[2][0].rect(0.).x() < sim.map.pos_to_coord_x(3));
+    Ok(())
+}
+
+#[test]
+fn explosion_only_hits_zombies_within_its_radius() -> Result<()> {
+    let mut sim = simulation(2, 150, &["cherry_bomb"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 4,
+        y: 2
+    }));
+    add_zombie(&mut sim, "normal", 1, 4)?;
+    add_zombie(&mut sim, "normal", 3, 5)?;
+    add_zombie(&mut sim, "normal", 2, 3)?;
+    add_zombie(&mut sim, "normal", 0, 4)?;
+    add_zombie(&mut sim, "normal", 2, 7)?;
+    let zombies_per_row =
+        |sim: &Simulation| sim.zombies.iter().map(Vec::len).collect::<Vec<usize>>();
+    run(&mut sim, Duration::from_millis(1100))?;
+    assert_eq!(zombies_per_row(&sim), vec![1, 1, 2, 1, 0]);
+    run(&mut sim, Duration::from_millis(200))?;
+    assert_eq!(zombies_per_row(&sim), vec![1, 0, 1, 0, 0]);
+    assert!(sim.zombies[2][0].rect(0.).x() > sim.map.pos_to_coord_x(6));
+    assert!(layers(&sim.plants[2][4]).is_empty());
+    Ok(())
+}
+
+#[test]
+fn trap_only_goes_off_once_armed() -> Result<()> {
+    let mut sim = simulation(1, 50, &["potato_mine"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 0
+    }));
+    add_zombie(&mut sim, "normal", 0, 3)?;
+    run(&mut sim, Duration::from_millis(2000))?;
+    assert_eq!(sim.zombies[0].len(), 1);
+    assert!(!layers(&sim.plants[0][3]).is_empty());
+
+    sim.zombies[0].clear();
+    sim.money = 25;
+    sim.shop_recharges[0] = Duration::ZERO;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 2
+    }));
+    run(&mut sim, Duration::from_secs(15))?;
+    add_zombie(&mut sim, "normal", 2, 4)?;
+    add_zombie(&mut sim, "normal", 1, 4)?;
+    run(&mut sim, Duration::from_secs(8))?;
+    assert!(sim.zombies[2].is_empty());
+    assert!(layers(&sim.plants[2][3]).is_empty());
+    assert_eq!(sim.zombies[1].len(), 1);
+    Ok(())
+}
+
+#[test]
+fn spikes_hurt_zombies_walking_over_them() -> Result<()> {
+    let mut sim = simulation(1, 100, &["spikeweed"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 0
+    }));
+    add_zombie(&mut sim, "normal", 0, 4)?;
+    let full_health = health(sim.zombies[0][0].as_ref());
+    run(&mut sim, Duration::from_secs(14))?;
+    let zombie = sim.zombies[0][0].as_ref();
+    assert!(zombie.rect(0.).right() < sim.map.pos_to_coord_x(3));
+    assert!(health(zombie) < full_health);
+    assert_eq!((full_health - health(zombie)) % 20, 0);
+    assert!(!layers(&sim.plants[0][3]).is_empty());
+    Ok(())
+}
+
+#[test]
+fn chomper_devours_the_nearest_zombie_then_digests() -> Result<()> {
+    let mut sim = simulation(1, 150, &["chomper"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 2,
+        y: 0
+    }));
+    add_zombie(&mut sim, "normal", 0, 3)?;
+    add_zombie(&mut sim, "normal", 0, 2)?;
+    sim.update(STEP)?;
+    assert_eq!(sim.zombies[0].len(), 1);
+    assert!(sim.zombies[0][0].rect(0.).x() > sim.map.pos_to_coord_x(3) - 0.01);
+
+    run(&mut sim, Duration::from_secs(5))?;
+    assert_eq!(sim.zombies[0].len(), 1);
+    assert!(layers(&sim.plants[0][2]).is_empty());
+    Ok(())
+}
+
+#[test]
+fn lobbed_projectile_splashes_zombies_near_its_landing() -> Result<()> {
+    let mut sim = simulation(1, 0, &[], &[(60_000, &["normal"])])?;
+    let definition = plant_definition(&sim, "melon_pult")?;
+    let PlantKind::Lobber { lob, .. } = definition.kind else {
+        return Err(anyhow!("melon_pult should be a lobber"));
+    };
+    add_zombie(&mut sim, "normal", 1, 5)?;
+    add_zombie(&mut sim, "normal", 1, 5)?;
+    add_zombie(&mut sim, "normal", 1, 8)?;
+    let target = sim.map.pos_to_coord_x(5);
+    sim.zombies[1][1].set_x(target + 50. / 1280.);
+    let full_health = health(sim.zombies[1][0].as_ref());
+    let hit_box = sim.zombies[1][0].hit_box(0.);
+    sim.projectiles[1].push(Box::new(Lobbed {
+        definition,
+        lob,
+        start_x: sim.map.pos_to_coord_x(0),
+        target_x: hit_box.x() + hit_box.width() / 2.,
+        target: Some(sim.zombies[1][0].uid()),
+        time: lob.flight,
+    }));
+    sim.update(STEP)?;
+
+    let damage = sim.zombies[1]
+        .iter()
+        .map(|zombie| full_health - health(zombie.as_ref()))
+        .collect::<Vec<u64>>();
+    assert_eq!(
+        damage,
+        vec![lob.damage_amount as u64, lob.splash_damage as u64, 0]
+    );
+    assert!(sim.projectiles[1].is_empty());
+    Ok(())
+}
+
+#[test]
+fn lobber_hits_the_first_zombie_in_its_row() -> Result<()> {
+    let mut sim = simulation(1, 100, &["cabbage_pult"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 0,
+        y: 1
+    }));
+    add_zombie(&mut sim, "normal", 1, 8)?;
+    add_zombie(&mut sim, "normal", 1, 6)?;
+    add_zombie(&mut sim, "normal", 2, 6)?;
+    let full_health = health(sim.zombies[1][0].as_ref());
+    run(&mut sim, Duration::from_millis(4200))?;
+    let damage = sim
+        .zombies
+        .iter()
+        .flatten()
+        .map(|zombie| full_health - health(zombie.as_ref()))
+        .collect::<Vec<u64>>();
+    assert_eq!(damage, vec![0, 40, 0]);
+    Ok(())
+}
+
+#[test]
+fn lobbed_projectile_follows_its_target() -> Result<()> {
+    let mut sim = simulation(1, 0, &[], &[(60_000, &["normal"])])?;
+    let definition = plant_definition(&sim, "cabbage_pult")?;
+    let PlantKind::Lobber { lob, .. } = definition.kind else {
+        return Err(anyhow!("cabbage_pult should be a lobber"));
+    };
+    add_zombie(&mut sim, "normal", 1, 3)?;
+    add_zombie(&mut sim, "normal", 1, 7)?;
+    let full_health = health(sim.zombies[1][0].as_ref());
+    let decoy = sim.zombies[1][0].hit_box(0.);
+    sim.projectiles[1].push(Box::new(Lobbed {
+        definition,
+        lob,
+        start_x: sim.map.pos_to_coord_x(0),
+        target_x: decoy.x() + decoy.width() / 2.,
+        target: Some(sim.zombies[1][1].uid()),
+        time: Duration::ZERO,
+    }));
+    run(&mut sim, lob.flight + STEP)?;
+
+    let damage = sim.zombies[1]
+        .iter()
+        .map(|zombie| full_health - health(zombie.as_ref()))
+        .collect::<Vec<u64>>();
+    assert_eq!(damage, vec![0, lob.damage_amount as u64]);
+    assert!(sim.projectiles[1].is_empty());
+    Ok(())
+}
+
+#[test]
+fn piercing_projectile_hits_each_zombie_once_up_to_its_pierce_count() -> Result<()> {
+    let mut sim = simulation(2, 0, &[], &[(60_000, &["normal"])])?;
+    let definition = plant_definition(&sim, "thorn_shooter")?;
+    let PlantKind::PiercingShooter { pierce, .. } = definition.kind else {
+        return Err(anyhow!("thorn_shooter should be a piercing shooter"));
+    };
+    for _ in 0..5 {
+        add_zombie(&mut sim, "normal", 2, 6)?;
+    }
+    let full_health = health(sim.zombies[2][0].as_ref());
+    let x = sim.map.pos_to_coord_x(6) - 40. / 1280.;
+    sim.projectiles[2].push(Box::new(Piercing {
+        definition,
+        pierce,
+        start_x: x,
+        x,
+        hits: Vec::new(),
+    }));
+    run(&mut sim, Duration::from_millis(600))?;
+    let damage = sim.zombies[2]
+        .iter()
+        .map(|zombie| full_health - health(zombie.as_ref()))
+        .collect::<Vec<u64>>();
+    assert_eq!(pierce.count, Some(3));
+    assert_eq!(damage, vec![20, 20, 20, 0, 0]);
+    assert!(sim.projectiles[2].is_empty());
+    Ok(())
+}
+
+#[test]
+fn torch_ignites_peas_passing_through_its_tile_once() -> Result<()> {
+    let mut sim = simulation(1, 175, &["torchwood"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 1
+    }));
+    let x = sim.map.pos_to_coord_x(2);
+    sim.projectiles[1].push(Box::new(Pea::new(x, DamageType::Normal)));
+    sim.projectiles[1].push(Box::new(Pea::new(x, DamageType::Ice)));
+    run(&mut sim, Duration::from_millis(1000))?;
+    let peas = sim.projectiles[1]
+        .iter()
+        .map(|pea| (pea.damage_type().name(), pea.damage_amount()))
+        .collect::<Vec<(&str, usize)>>();
+    assert_eq!(peas, vec![("fire", 40), ("normal", 20)]);
+    Ok(())
+}
+
+#[test]
+fn triple_peashooter_fires_into_its_row_and_the_ones_beside_it() -> Result<()> {
+    let mut sim = simulation(2, 325, &["triple_peashooter"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 0,
+        y: 0
+    }));
+    for row in 0..3 {
+        add_zombie(&mut sim, "normal", row, 6)?;
+    }
+    let full_health = health(sim.zombies[0][0].as_ref());
+    run(&mut sim, Duration::from_millis(5000))?;
+    let damaged = sim.zombies[..3]
+        .iter()
+        .map(|row| health(row[0].as_ref()) < full_health)
+        .collect::<Vec<bool>>();
+    assert_eq!(damaged, vec![true, true, false]);
+    Ok(())
+}
+
+#[test]
+fn split_peashooter_fires_both_ways() -> Result<()> {
+    let mut sim = simulation(2, 125, &["split_pea"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 4,
+        y: 2
+    }));
+    add_zombie(&mut sim, "normal", 2, 1)?;
+    add_zombie(&mut sim, "normal", 2, 8)?;
+    let full_health = health(sim.zombies[2][0].as_ref());
+    run(&mut sim, Duration::from_millis(3500))?;
+    let damage = sim.zombies[2]
+        .iter()
+        .map(|zombie| full_health - health(zombie.as_ref()))
+        .collect::<Vec<u64>>();
+    assert_eq!(damage, vec![40, 20]);
+    Ok(())
+}
+
+#[test]
+fn starfruit_hits_zombies_in_other_rows() -> Result<()> {
+    let mut sim = simulation(2, 125, &["starfruit"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 4,
+        y: 2
+    }));
+    add_zombie(&mut sim, "normal", 0, 4)?;
+    let full_health = health(sim.zombies[0][0].as_ref());
+    run(&mut sim, Duration::from_millis(3000))?;
+    assert!(health(sim.zombies[0][0].as_ref()) < full_health);
+    Ok(())
+}
+
+#[test]
+fn cattail_homes_onto_the_closest_zombie() -> Result<()> {
+    let mut sim = simulation(2, 225, &["cattail"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 2,
+        y: 0
+    }));
+    add_zombie(&mut sim, "normal", 4, 8)?;
+    add_zombie(&mut sim, "normal", 3, 6)?;
+    let full_health = health(sim.zombies[4][0].as_ref());
+    run(&mut sim, Duration::from_millis(6000))?;
+    assert_eq!(health(sim.zombies[4][0].as_ref()), full_health);
+    assert!(health(sim.zombies[3][0].as_ref()) < full_health);
+    Ok(())
+}
+
+#[test]
+fn homing_star_retargets_when_its_zombie_is_gone() -> Result<()> {
+    let mut sim = simulation(2, 0, &[], &[(60_000, &["normal"])])?;
+    let definition = plant_definition(&sim, "cattail")?;
+    add_zombie(&mut sim, "normal", 3, 6)?;
+    let full_health = health(sim.zombies[3][0].as_ref());
+    sim.projectiles[0].push(Box::new(Star {
+        definition,
+        damage_amount: 20,
+        x: sim.map.pos_to_coord_x(2),
+        y: sim.map.pos_to_coord_y(0),
+        direction: (1., 0.),
+        target: Some(sim.next_zombie_uid + 1),
+    }));
+    run(&mut sim, Duration::from_secs(4))?;
+    assert_eq!(health(sim.zombies[3][0].as_ref()), full_health - 20);
+    assert!(sim.projectiles.iter().all(Vec::is_empty));
+    Ok(())
+}
+
+#[test]
+fn shield_only_guards_hits_from_the_front() -> Result<()> {
+    let mut sim = simulation(1, 0, &[], &[(60_000, &["normal"])])?;
+    add_zombie(&mut sim, "screen_door", 1, 5)?;
+    let full_armor = armor(sim.zombies[1][0].as_ref());
+    let full_health = health(sim.zombies[1][0].as_ref());
+    sim.projectiles[1].push(Box::new(Pea::new(
+        sim.map.pos_to_coord_x(4),
+        DamageType::Normal,
+    )));
+    sim.projectiles[1].push(Box::new(Pea::backward(
+        sim.map.pos_to_coord_x(7),
+        DamageType::Normal,
+    )));
+    run(&mut sim, Duration::from_millis(1500))?;
+    assert!(sim.projectiles[1].is_empty());
+    assert_eq!(armor(sim.zombies[1][0].as_ref()), vec![full_armor[0] - 20]);
+    assert_eq!(health(sim.zombies[1][0].as_ref()), full_health - 20);
+    Ok(())
+}
+
+#[test]
+fn lobbed_projectile_lands_behind_the_shield() -> Result<()> {
+    let mut sim = simulation(1, 0, &[], &[(60_000, &["normal"])])?;
+    let definition = plant_definition(&sim, "cabbage_pult")?;
+    let PlantKind::Lobber { lob, .. } = definition.kind else {
+        return Err(anyhow!("cabbage_pult should be a lobber"));
+    };
+    add_zombie(&mut sim, "screen_door", 1, 5)?;
+    let full_armor = armor(sim.zombies[1][0].as_ref());
+    let full_health = health(sim.zombies[1][0].as_ref());
+    let hit_box = sim.zombies[1][0].hit_box(0.);
+    sim.projectiles[1].push(Box::new(Lobbed {
+        definition,
+        lob,
+        start_x: sim.map.pos_to_coord_x(0),
+        target_x: hit_box.x() + hit_box.width() / 2.,
+        target: Some(sim.zombies[1][0].uid()),
+        time: lob.flight,
+    }));
+    sim.update(STEP)?;
+    assert!(sim.projectiles[1].is_empty());
+    assert_eq!(armor(sim.zombies[1][0].as_ref()), full_armor);
+    assert_eq!(
+        health(sim.zombies[1][0].as_ref()),
+        full_health - lob.damage_amount as u64
+    );
+    Ok(())
+}
+
+#[test]
+fn pole_vault_jumps_over_plants_but_not_tall_ones() -> Result<()> {
+    let mut sim = simulation(2, 175, &["wall", "tall_wall"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 1
+    }));
+    assert!(sim.command(Command::PlacePlant {
+        plant: 1,
+        x: 3,
+        y: 3
+    }));
+    add_zombie(&mut sim, "pole_vault", 1, 5)?;
+    add_zombie(&mut sim, "pole_vault", 3, 5)?;
+    run(&mut sim, Duration::from_secs(10))?;
+    let wall_left = sim.map.pos_to_coord_x(3);
+    let vaulter = &sim.zombies[1][0];
+    assert!(vaulter.rect(0.).right() <= wall_left);
+    assert_eq!(layers(&sim.plants[1][3]), vec!["wall"]);
+    let blocked = &sim.zombies[3][0];
+    assert!(blocked.rect(0.).left() > wall_left);
+    assert_eq!(layers(&sim.plants[3][3]), vec!["tall_wall"]);
+    Ok(())
+}
+
+#[test]
+fn frozen_zombies_eat_at_their_frozen_speed() -> Result<()> {
+    let mut sim = simulation(1, 50, &["wall"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 0
+    }));
+    add_zombie(&mut sim, "tank", 0, 3)?;
+    let tank = zombie_definition(sim.zombie_registry, "tank")?;
+    sim.zombies[0][0].hit(Hit::new(0, DamageType::Ice, HitSource::Front));
+    assert!(sim.zombies[0][0].freezed());
+    let wall_health = |sim: &Simulation| {
+        sim.plants[0][3]
+            .plants()
+            .next()
+            .and_then(|wall| wall.save()["health_ns"].as_u64())
+            .unwrap_or_default()
+    };
+    let full_health = wall_health(&sim);
+    run(&mut sim, Duration::from_secs(1))?;
+    let eaten = Duration::from_nanos(full_health - wall_health(&sim)).as_secs_f32();
+    assert!((eaten - tank.eat_rate * tank.frozen_speed).abs() < 0.01);
+    Ok(())
+}
+
+#[test]
+fn balloon_floats_over_ground_plants() -> Result<()> {
+    let mut sim = simulation(1, 50, &["wall"], &[(60_000, &["normal"])])?;
+    assert!(sim.command(Command::PlacePlant {
+        plant: 0,
+        x: 3,
+        y: 1
+    }));
+    add_zombie(&mut sim, "balloon", 1, 4)?;
+    run(&mut sim, Duration::from_secs(10))?;
+    assert!(sim.zombies[1][0].rect(0.).right() <= sim.map.pos_to_coord_x(3));
+    let wall = sim.plants[1][3]
+        .plants()
+        .next()
+        .ok_or_else(|| anyhow!("the wall should still stand"))?;
+    assert_eq!(
+        wall.save()["health_ns"].as_u64(),
+        Some(wall.definition().health.as_nanos() as u64)
+    );
+    Ok(())
+}
+
+#[test]
+fn only_anti_air_projectiles_hit_balloons() -> Result<()> {
+    let mut sim = simulation(2, 0, &[], &[(60_000, &["normal"])])?;
+    let definition = plant_definition(&sim, "thorn_shooter")?;
+    let PlantKind::PiercingShooter { pierce, .. } = definition.kind else {
+        return Err(anyhow!("thorn_shooter should be a piercing shooter"));
+    };
+    assert!(definition.anti_air);
+    add_zombie(&mut sim, "balloon", 2, 6)?;
+    let full_health = health(sim.zombies[2][0].as_ref());
+    let x = sim.map.pos_to_coord_x(5);
+    sim.projectiles[2].push(Box::new(Pea::new(x, DamageType::Normal)));
+    run(&mut sim, Duration::from_millis(1500))?;
+    let balloon = sim.zombies[2][0].hit_box(0.);
+    assert!(sim.projectiles[2]
+        .iter()
+        .all(|pea| pea.rect(0.).left() > balloon.right()));
+    assert!(sim.zombies[2][0].airborne());
+    assert_eq!(armor(sim.zombies[2][0].as_ref()), vec![20]);
+
+    sim.projectiles[2].push(Box::new(Piercing {
+        definition,
+        pierce,
+        start_x: x,
+        x,
+        hits: Vec::new(),
+    }));
+    run(&mut sim, Duration::from_millis(1500))?;
+    assert!(!sim.zombies[2][0].airborne());
+    assert_eq!(armor(sim.zombies[2][0].as_ref()), vec![0]);
+    assert_eq!(health(sim.zombies[2][0].as_ref()), full_health);
+    Ok(())
+}
+
+#[test]
+fn shooters_hold_fire_at_zombies_they_cannot_reach() -> Result<()> {
+    let mut sim = simulation(
+        2,
+        1000,
+        &["peashooter", "thorn_shooter"],
+        &[(60_000, &["normal"])],
+    )?;
+    assert!(place(&mut sim, 0, 0, 1));
+    assert!(place(&mut sim, 1, 0, 3));
+    add_zombie(&mut sim, "balloon", 1, 7)?;
+    add_zombie(&mut sim, "balloon", 3, 7)?;
+    run(&mut sim, Duration::from_secs(3))?;
+    assert!(sim.projectiles[1].is_empty());
+    assert!(!sim.projectiles[3].is_empty());
+    Ok(())
+}
+
+#[test]
+fn zombies_only_spawn_in_rows_they_can_move_in() -> Result<()> {
+    let wave = ["swimmer", "normal"].repeat(10);
+    let mut sim = simulation(3, 0, &[], &[(0, &wave)])?;
+    sim.update(STEP)?;
+    for (row, zombies) in sim.zombies.iter().enumerate() {
+        let water = sim.map.rows[row] == RowType::Water;
+        assert!(zombies
+            .iter()
+            .all(|zombie| (zombie.save()["type"] == "swimmer") == water));
+    }
+    assert_eq!(sim.zombies.iter().flatten().count(), 20);
+    Ok(())
+}
+
+#[test]
+fn zombies_without_a_row_to_move_in_are_rejected() -> Result<()> {
+    assert!(simulation(1, 0, &[], &[(0, &["swimmer"])]).is_err());
+
+    let sim = simulation(1, 0, &[], &[(60_000, &["normal"])])?;
+    let state = sim.save_state();
+    for (key, value, field) in [
+        ("type", json!("swimmer"), "waves[0].zombies[0].type"),
+        ("lane", json!(7), "waves[0].zombies[0].lane"),
+    ] {
+        let mut state = state.clone();
+        state["waves"][0]["zombies"][0][key] = value;
+        let Err(error) = Simulation::load_state(
+            &Field::root(SUSPEND_FILE, &state),
+            sim.zombie_registry,
+            sim.plant_registry,
+        ) else {
+            return Err(anyhow!(
+                "a zombie that cannot move in the map should not load"
+            ));
+        };
+        assert_eq!(
+            invalid_field(error)?,
+            (SUSPEND_FILE.to_owned(), field.to_owned())
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn lobber_ignores_submerged_zombies() -> Result<()> {
+    let mut sim = simulation(
+        3,
+        1000,
+        &["nenuphar", "cabbage_pult"],
+        &[(60_000, &["normal"])],
+    )?;
+    assert!(place(&mut sim, 0, 0, 2));
+    assert!(place(&mut sim, 1, 0, 2));
+    add_zombie(&mut sim, "snorkel", 2, 7)?;
+    let full_health = health(sim.zombies[2][0].as_ref());
+    assert!(sim.zombies[2][0].submerged());
+    for _ in 0..50 {
+        run(&mut sim, Duration::from_millis(100))?;
+        assert!(sim.projectiles[2].is_empty());
+    }
+    assert_eq!(health(sim.zombies[2][0].as_ref()), full_health);
+    Ok(())
+}
@@ -2,19 +2,20 @@ use anyhow::Result;
 use rand::Rng;
 use std::time::Duration;
 
-use crate::{sun::Sun, zombie::zombie_from_id};
+use crate::{projectile::Impact, sun::Sun, zombie::reachable};
 
 use super::{
-    collision::{do_damage_to_plant, do_damage_to_zombies},
-    Level,
+    collision::{do_damage_to_plant, do_damage_to_zombies, do_landing_damage, do_piercing_damage},
+    simulation::Simulation,
 };
 
-impl Level {
+impl Simulation {
     pub(super) fn update_zombies(&mut self, elapsed: Duration) -> Result<()> {
         for (y, zombies) in self.zombies.iter_mut().enumerate() {
             for zombie in zombies.iter_mut() {
                 let prev_x = zombie.rect(0.).x();
                 zombie.update(elapsed)?;
+                self.closest_zombie = self.closest_zombie.min(zombie.hit_box(0.).x());
 
                 if zombie.rect(0.).x() + zombie.rect(0.).width() < self.map.left {
                     self.end = Some(false);
@@ -22,10 +23,8 @@ impl Level {
                 } else {
                     do_damage_to_plant(
                         zombie.as_mut(),
-                        y,
-                        &mut self.map_plants,
+                        &mut self.plants[y],
                         &self.map,
-                        self.map.rows[y],
                         prev_x,
                         elapsed,
                     );
@@ -36,21 +35,76 @@ impl Level {
     }
 
     pub(super) fn update_projectiles(&mut self, elapsed: Duration) -> Result<()> {
+        let mut moved = Vec::new();
         for (y, projs) in self.projectiles.iter_mut().enumerate() {
             let mut indx = Vec::new();
             for (i, proj) in projs.iter_mut().enumerate() {
+                if let Some(uid) = proj.target() {
+                    let reaches_air = proj.reaches_air();
+                    match self.zombies.iter().enumerate().find_map(|(row, zombies)| {
+                        zombies
+                            .iter()
+                            .find(|zombie| {
+                                zombie.uid() == uid && reachable(zombie.as_ref(), reaches_air)
+                            })
+                            .map(|zombie| (row, zombie))
+                    }) {
+                        Some((row, zombie)) => {
+                            let hit_box = zombie.hit_box(
+                                self.map.pos_to_coord_y(row) + self.map.row_heigth()
+                                    - zombie.rect(0.).height(),
+                            );
+                            proj.aim(
+                                hit_box.x() + hit_box.width() / 2.,
+                                hit_box.y() + hit_box.height() / 2.,
+                            );
+                        }
+                        None => proj.retarget(&self.zombies),
+                    }
+                }
                 proj.update(elapsed)?;
 
-                let proj = proj.as_ref();
+                let proj = proj.as_mut();
+
+                let lane = proj.rect(
+                    self.map.pos_to_coord_y(y) + self.map.row_heigth() / 2.
+                        - proj.rect(0.).height() / 2.,
+                );
+                match self.map.coord_to_pos_y(lane.y() + lane.height() / 2.) {
+                    Some(row) if row == y => {}
+                    row => {
+                        indx.insert(0, (i, row));
+                        continue;
+                    }
+                }
+
+                let rect = proj.rect(0.);
+                if let Some(x) = self.map.coord_to_pos_x(rect.x() + rect.width() / 2.) {
+                    for plant in self.plants[y][x].plants() {
+                        plant.transform_projectile(x, proj);
+                    }
+                }
 
                 if proj.to_remove() {
-                    indx.insert(0, i);
+                    indx.insert(0, (i, None));
                     continue;
                 }
 
-                let mut zombie_to_remove = do_damage_to_zombies(self.zombies[y].as_mut(), proj);
+                let mut zombie_to_remove = match proj.impact() {
+                    Impact::Contact => do_damage_to_zombies(self.zombies[y].as_mut(), proj),
+                    Impact::Piercing => (false, do_piercing_damage(self.zombies[y].as_mut(), proj)),
+                    Impact::InFlight => continue,
+                    Impact::Landing {
+                        splash_radius,
+                        splash_damage,
+                    } => {
+                        do_landing_damage(&mut self.zombies[y], proj, splash_radius, splash_damage);
+                        indx.insert(0, (i, None));
+                        continue;
+                    }
+                };
                 if zombie_to_remove.0 {
-                    indx.insert(0, i);
+                    indx.insert(0, (i, None));
                 }
                 zombie_to_remove.1.sort();
                 zombie_to_remove.1.reverse();
@@ -59,10 +113,16 @@ impl Level {
                     self.zombies[y].remove(zombie_index);
                 }
             }
-            for i in indx {
-                projs.remove(i);
+            for (i, row) in indx {
+                let proj = projs.remove(i);
+                if let Some(row) = row {
+                    moved.push((row, proj));
+                }
             }
         }
+        for (row, proj) in moved {
+            self.projectiles[row].push(proj);
+        }
         Ok(())
     }
 
@@ -74,11 +134,10 @@ impl Level {
             self.next_sun -= elapsed
         } else {
             self.next_sun = Duration::new(5, 0) - elapsed + self.next_sun;
-            let mut rng = rand::rng();
             self.suns.push(Sun::new(
-                rng.random_range(0.0..1.0),
+                self.rng.random_range(0.0..1.0),
                 0.,
-                rng.random_range(200.0..420.) / 720.,
+                self.rng.random_range(200.0..420.) / 720.,
             ));
         }
         Ok(())
@@ -91,13 +150,21 @@ impl Level {
                     elapsed -= f;
                     self.spawn_waits.remove(0);
                     let mut z = self.spawn_zombies.remove(0);
-                    let mut rng = rand::rng();
                     let mut offsets: Vec<f32> = (0..self.map.rows.len()).map(|_| 1.).collect();
                     while !z.is_empty() {
-                        let i = rng.random_range(0..z.len());
-                        let mut z = zombie_from_id(z.remove(i).0);
-                        let i = rng.random_range(0..self.map.rows.len()) as usize;
+                        let i = self.rng.random_range(0..z.len());
+                        let spawn = z.remove(i);
+                        let mut z = self.zombie_registry.spawn(spawn.id);
+                        let Some(i) = spawn
+                            .lane
+                            .map(usize::from)
+                            .or_else(|| self.random_lane(spawn.id))
+                        else {
+                            continue;
+                        };
                         z.set_x(offsets[i]);
+                        z.set_uid(self.next_zombie_uid);
+                        self.next_zombie_uid += 1;
                         offsets[i] += 7.68 / 1280.;
                         self.zombies[i].push(z);
                     }
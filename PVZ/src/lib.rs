@@ -1,16 +1,22 @@
 #![feature(ptr_as_ref_unchecked)]
 
 use anyhow::Result;
+use level::suspend::SUSPEND_FILE;
+use plants::registry::PlantRegistry;
 use red_sdl::{functions::StateEnum, ui_element::ui_rect::UIRect};
+use replay::Replay;
 use save::SaveFile;
 use sdl2::pixels::Color;
+use std::path::Path;
 use texts::{Lang, Texts};
 use textures::Textures;
+use zombie::registry::ZombieRegistry;
 
 pub mod level;
 pub mod map_plant;
 pub mod plants;
 pub mod projectile;
+pub mod replay;
 pub mod save;
 pub mod shop_plant;
 pub mod sun;
@@ -28,19 +34,37 @@ pub fn default_button<Parent, State>() -> UIRect<Parent, State> {
 
 pub struct State {
     levels_count: u8,
+    seed: Option<u64>,
+    replay: Option<Replay>,
     save: SaveFile,
+    suspended: bool,
     textures: Textures,
+    zombies: &'static ZombieRegistry,
+    plants: &'static PlantRegistry,
     update_available: Option<Result<bool>>,
     en: Texts,
     fr: Texts,
 }
 
 impl State {
-    pub fn new(levels_count: u8, save: SaveFile, textures: Textures) -> Self {
+    pub fn new(
+        levels_count: u8,
+        seed: Option<u64>,
+        replay: Option<Replay>,
+        save: SaveFile,
+        textures: Textures,
+        zombies: &'static ZombieRegistry,
+        plants: &'static PlantRegistry,
+    ) -> Self {
         Self {
             levels_count,
+            seed,
+            replay,
             save,
+            suspended: Path::new(SUSPEND_FILE).exists(),
             textures,
+            zombies,
+            plants,
             update_available: None,
             en: Texts::default(),
             fr: Texts::default(),
@@ -51,6 +75,14 @@ impl State {
         &self.textures
     }
 
+    pub const fn zombies(&self) -> &'static ZombieRegistry {
+        self.zombies
+    }
+
+    pub const fn plants(&self) -> &'static PlantRegistry {
+        self.plants
+    }
+
     pub const fn texts(&self) -> &Texts {
         match self.save.langage {
             Lang::EN => &self.en,
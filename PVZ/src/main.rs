@@ -1,22 +1,54 @@
-use std::fs;
+use std::{env, fs};
 
 use anyhow::{anyhow, Result};
-use pvz::{save::SaveFile, textures::load_textures, win::Win, State};
+use pvz::{
+    level::config::upgrade_legacy_files, plants::registry::PlantRegistry, replay::Replay,
+    save::SaveFile, textures::load_textures, win::Win, zombie::registry::ZombieRegistry, State,
+};
 use red_sdl::run_game;
 
 pub fn main() -> Result<()> {
+    if env::args().any(|arg| arg == "--convert") {
+        return upgrade_legacy_files();
+    }
     run_game(
         "Plant Vs Zombie",
         1280,
         720,
         |window| window.fullscreen_desktop().resizable(),
         |canvas| {
-            let textures = load_textures(canvas, Box::leak(Box::new(canvas.texture_creator())))?;
+            let zombies: &'static ZombieRegistry = Box::leak(Box::new(ZombieRegistry::load()?));
+            let plants: &'static PlantRegistry = Box::leak(Box::new(PlantRegistry::load()?));
+            let textures = load_textures(
+                canvas,
+                Box::leak(Box::new(canvas.texture_creator())),
+                zombies,
+                plants,
+            )?;
             let levels_count = fs::read_dir("levels").map_err(|e| anyhow!(e))?.count();
             if levels_count == 0 || fs::read_dir("levels").map_err(|e| anyhow!(e))?.count() > 99 {
                 return Err(anyhow!("Too much or no levels"));
             }
-            Ok(State::new(levels_count as u8, SaveFile::load()?, textures))
+            let seed = env::args()
+                .skip_while(|arg| arg != "--seed")
+                .nth(1)
+                .map(|seed| seed.parse::<u64>())
+                .transpose()
+                .map_err(|e| anyhow!(e))?;
+            let replay = env::args()
+                .skip_while(|arg| arg != "--replay")
+                .nth(1)
+                .map(Replay::load)
+                .transpose()?;
+            Ok(State::new(
+                levels_count as u8,
+                seed,
+                replay,
+                SaveFile::load()?,
+                textures,
+                zombies,
+                plants,
+            ))
         },
         Win::new,
     )
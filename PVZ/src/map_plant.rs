@@ -8,15 +8,11 @@ use red_sdl::{
 };
 use sdl2::{rect::FRect, render::Canvas, video::Window};
 
-use crate::{
-    level::{config::RowType, Level},
-    plants::Plant,
-    State,
-};
+use crate::{level::Level, State};
 
 pub struct MapPlant {
-    pub row_type: RowType,
-    pub plant: Option<Box<dyn Plant>>,
+    pub x: usize,
+    pub y: usize,
     pub surface: FRect,
 }
 
@@ -59,19 +55,10 @@ impl UserControl<Level, State> for MapPlant {
     fn draw(
         this: Ref<Self>,
         canvas: &mut Canvas<Window>,
-        _: Ref<Level>,
+        parent: Ref<Level>,
         state: Ref<State>,
     ) -> Result<()> {
-        if let Some(plant) = this.as_ref().plant.as_ref() {
-            if !plant.can_go_in_water() && this.row_type == RowType::Water {
-                canvas
-                    .copy_f(
-                        state.as_ref().textures().plant_nenuphar(),
-                        None,
-                        this.surface,
-                    )
-                    .map_err(|e| anyhow!(e))?;
-            }
+        for plant in parent.as_ref().sim.plants[this.y][this.x].plants() {
             canvas
                 .copy_f(plant.texture(state), None, this.surface)
                 .map_err(|e| anyhow!(e))?;
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use red_sdl::refs::Ref;
+use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
+
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::Projectile,
+    sun::Sun,
+    zombie::Zombie,
+    State,
+};
+
+use super::{in_tile, registry::PlantDefinition, Plant};
+
+#[derive(Clone)]
+pub struct Chomper {
+    definition: &'static PlantDefinition,
+    digest: Duration,
+    digesting: Duration,
+    health: Duration,
+}
+impl Chomper {
+    pub const fn new(definition: &'static PlantDefinition, digest: Duration) -> Self {
+        Self {
+            definition,
+            digest,
+            digesting: Duration::ZERO,
+            health: definition.health,
+        }
+    }
+
+    pub fn load(
+        definition: &'static PlantDefinition,
+        digest: Duration,
+        json: &Field,
+    ) -> Result<Self, LoadError> {
+        Ok(Self {
+            definition,
+            digest,
+            digesting: json.get("digesting_ns").duration()?,
+            health: json.get("health_ns").duration()?,
+        })
+    }
+}
+impl Plant for Chomper {
+    fn texture(&self, state: Ref<State>) -> &'static Texture {
+        let texture = if self.digesting.is_zero() {
+            0
+        } else {
+            self.definition.textures.len() - 1
+        };
+        state.as_ref().textures().plant(self.definition.id, texture)
+    }
+
+    fn rect(&self, x: f32, y: f32) -> FRect {
+        FRect::new(x, y, 70. / 1280., 100. / 720.)
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        self.digesting = self.digesting.saturating_sub(elapsed);
+        Ok(())
+    }
+
+    fn cost(&self) -> u32 {
+        self.definition.cost
+    }
+
+    fn clone(&self) -> Box<dyn Plant> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
+    }
+
+    fn blocks_zombies(&self) -> bool {
+        true
+    }
+
+    fn should_spawn(
+        &mut self,
+        _: f32,
+        _: f32,
+        _: usize,
+        _: usize,
+        _: &[Vec<Box<dyn Zombie>>],
+    ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
+        (Vec::new(), Vec::new())
+    }
+
+    fn act_on_tile(&mut self, left: f32, right: f32, zombies: &mut Vec<Box<dyn Zombie>>) -> bool {
+        if !self.digesting.is_zero() {
+            return false;
+        }
+        let reach = right + (right - left);
+        let target = zombies
+            .iter()
+            .enumerate()
+            .filter(|(_, zombie)| in_tile(zombie.as_ref(), left, reach))
+            .min_by(|(_, z1), (_, z2)| z1.hit_box(0.).left().total_cmp(&z2.hit_box(0.).left()))
+            .map(|(i, _)| i);
+        if let Some(i) = target {
+            zombies.remove(i);
+            self.digesting = self.digest;
+        }
+        false
+    }
+
+    fn health(&mut self) -> &mut Duration {
+        &mut self.health
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "digesting_ns": duration_value(self.digesting),
+            "health_ns": duration_value(self.health),
+        })
+    }
+}
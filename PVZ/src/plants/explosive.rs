@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use red_sdl::refs::Ref;
+use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
+
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::{DamageType, Projectile},
+    sun::Sun,
+    zombie::Zombie,
+    State,
+};
+
+use super::{registry::PlantDefinition, AreaDamage, Plant};
+
+#[derive(Clone, Copy)]
+pub struct Explosion {
+    pub fuse: Duration,
+    pub damage_amount: usize,
+    pub damage_type: DamageType,
+    pub row_radius: usize,
+    pub col_radius: Option<f32>,
+}
+
+impl Explosion {
+    pub fn load(json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            fuse: Duration::from_millis(json.get("fuse_ms").u64()?),
+            damage_amount: json.get("damage").u64()? as usize,
+            damage_type: DamageType::load(&json.get("damage_type"))?,
+            row_radius: json.get("row_radius").u8()? as usize,
+            col_radius: json
+                .get("col_radius")
+                .optional()
+                .map(|radius| radius.f32())
+                .transpose()?,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct Explosive {
+    definition: &'static PlantDefinition,
+    explosion: Explosion,
+    fuse: Duration,
+    health: Duration,
+}
+impl Explosive {
+    pub const fn new(definition: &'static PlantDefinition, explosion: Explosion) -> Self {
+        Self {
+            definition,
+            explosion,
+            fuse: explosion.fuse,
+            health: definition.health,
+        }
+    }
+
+    pub fn load(
+        definition: &'static PlantDefinition,
+        explosion: Explosion,
+        json: &Field,
+    ) -> Result<Self, LoadError> {
+        Ok(Self {
+            definition,
+            explosion,
+            fuse: json.get("fuse_ns").duration()?,
+            health: json.get("health_ns").duration()?,
+        })
+    }
+}
+impl Plant for Explosive {
+    fn texture(&self, state: Ref<State>) -> &'static Texture {
+        state.as_ref().textures().plant(self.definition.id, 0)
+    }
+
+    fn rect(&self, x: f32, y: f32) -> FRect {
+        FRect::new(x, y, 70. / 1280., 100. / 720.)
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        self.fuse = self.fuse.saturating_sub(elapsed);
+        Ok(())
+    }
+
+    fn cost(&self) -> u32 {
+        self.definition.cost
+    }
+
+    fn clone(&self) -> Box<dyn Plant> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
+    }
+
+    fn blocks_zombies(&self) -> bool {
+        true
+    }
+
+    fn should_spawn(
+        &mut self,
+        _: f32,
+        _: f32,
+        _: usize,
+        _: usize,
+        _: &[Vec<Box<dyn Zombie>>],
+    ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
+        (Vec::new(), Vec::new())
+    }
+
+    fn detonate(&mut self, x: f32, y: usize, col_width: f32) -> Option<AreaDamage> {
+        if !self.fuse.is_zero() {
+            return None;
+        }
+        let (left, right) = match self.explosion.col_radius {
+            Some(radius) => (x - radius * col_width, x + radius * col_width),
+            None => (f32::MIN, f32::MAX),
+        };
+        Some(AreaDamage {
+            rows: y.saturating_sub(self.explosion.row_radius)..=y + self.explosion.row_radius,
+            left,
+            right,
+            damage_amount: self.explosion.damage_amount,
+            damage_type: self.explosion.damage_type,
+        })
+    }
+
+    fn health(&mut self) -> &mut Duration {
+        &mut self.health
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "fuse_ns": duration_value(self.fuse),
+            "health_ns": duration_value(self.health),
+        })
+    }
+}
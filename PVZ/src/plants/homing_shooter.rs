@@ -0,0 +1,43 @@
+use crate::{
+    projectile::{homing_target, Projectile, Star},
+    zombie::Zombie,
+};
+
+use super::{registry::PlantDefinition, shooter::Shot};
+
+#[derive(Clone)]
+pub struct HomingShot {
+    pub damage_amount: usize,
+}
+impl Shot for HomingShot {
+    fn in_range(
+        &self,
+        definition: &'static PlantDefinition,
+        _: f32,
+        _: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> bool {
+        homing_target(zombies, definition.anti_air).is_some()
+    }
+
+    fn fire(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        y: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> Vec<(usize, Box<dyn Projectile>)> {
+        vec![(
+            y_pos,
+            Box::new(Star {
+                definition,
+                damage_amount: self.damage_amount,
+                x: x - 25. / 1280.,
+                y: y + 25. / 720.,
+                direction: (1., 0.),
+                target: homing_target(zombies, definition.anti_air),
+            }),
+        )]
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use crate::{
+    projectile::{Lob, Lobbed, Projectile},
+    zombie::{reachable, Zombie},
+};
+
+use super::{registry::PlantDefinition, shooter::Shot};
+
+#[derive(Clone)]
+pub struct LobShot {
+    pub lob: Lob,
+}
+impl LobShot {
+    fn target(x: f32, zombies: &[Box<dyn Zombie>]) -> Option<&dyn Zombie> {
+        zombies
+            .iter()
+            .map(Box::as_ref)
+            .filter(|zombie| reachable(*zombie, false) && zombie.hit_box(0.).right() > x)
+            .min_by(|zombie1, zombie2| {
+                zombie1
+                    .hit_box(0.)
+                    .left()
+                    .total_cmp(&zombie2.hit_box(0.).left())
+            })
+    }
+}
+impl Shot for LobShot {
+    fn in_range(
+        &self,
+        _: &'static PlantDefinition,
+        x: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> bool {
+        Self::target(x, &zombies[y_pos]).is_some()
+    }
+
+    fn fire(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        _: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> Vec<(usize, Box<dyn Projectile>)> {
+        let Some(target) = Self::target(x, &zombies[y_pos]) else {
+            return Vec::new();
+        };
+        let hit_box = target.hit_box(0.);
+        vec![(
+            y_pos,
+            Box::new(Lobbed {
+                definition,
+                lob: self.lob,
+                start_x: x,
+                target_x: hit_box.x() + hit_box.width() / 2.,
+                target: Some(target.uid()),
+                time: Duration::ZERO,
+            }),
+        )]
+    }
+}
@@ -1,15 +1,37 @@
-use std::time::Duration;
+use std::{ops::RangeInclusive, time::Duration};
 
 use anyhow::Result;
 use red_sdl::refs::Ref;
 use sdl2::{rect::FRect, render::Texture};
+use serde_json::Value;
 
-use crate::{projectile::Projectile, sun::Sun, zombie::Zombie, State};
+use crate::{
+    level::parse::{Field, LoadError},
+    projectile::{DamageType, Projectile},
+    sun::Sun,
+    zombie::{reachable, Zombie},
+    State,
+};
+use registry::{PlantDefinition, PlantRegistry};
 
+pub mod chomper;
+pub mod explosive;
+pub mod homing_shooter;
+pub mod lobber;
 pub mod nenuphar;
 pub mod peashooter;
+pub mod piercing_shooter;
+pub mod registry;
+pub mod shooter;
+pub mod spikes;
+pub mod split_peashooter;
+pub mod star_shooter;
 pub mod sunflower;
+pub mod tile;
+pub mod torch;
+pub mod trap;
 pub mod triple_peashooter;
+pub mod wall;
 
 pub trait Plant {
     fn texture(&self, state: Ref<State>) -> &'static Texture;
@@ -17,9 +39,9 @@ pub trait Plant {
     fn update(&mut self, elapsed: Duration) -> Result<()>;
 
     fn clone(&self) -> Box<dyn Plant>;
+    fn definition(&self) -> &'static PlantDefinition;
     fn cost(&self) -> u32;
-    fn can_go_in_water(&self) -> bool;
-    fn is_nenuphar(&self) -> bool;
+    fn blocks_zombies(&self) -> bool;
     #[allow(clippy::type_complexity)]
     fn should_spawn(
         &mut self,
@@ -29,5 +51,43 @@ pub trait Plant {
         max_y_pos: usize,
         zombies: &[Vec<Box<dyn Zombie>>],
     ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>);
+    fn detonate(&mut self, _x: f32, _y: usize, _col_width: f32) -> Option<AreaDamage> {
+        None
+    }
+    fn act_on_tile(
+        &mut self,
+        _left: f32,
+        _right: f32,
+        _zombies: &mut Vec<Box<dyn Zombie>>,
+    ) -> bool {
+        false
+    }
+    fn transform_projectile(&self, _x: usize, _proj: &mut dyn Projectile) {}
     fn health(&mut self) -> &mut Duration;
+    fn save(&self) -> Value;
+}
+
+pub struct AreaDamage {
+    pub rows: RangeInclusive<usize>,
+    pub left: f32,
+    pub right: f32,
+    pub damage_amount: usize,
+    pub damage_type: DamageType,
+}
+
+pub fn in_tile(zombie: &dyn Zombie, left: f32, right: f32) -> bool {
+    let rect = zombie.rect(0.);
+    reachable(zombie, false) && rect.right() > left && rect.left() < right
+}
+
+pub fn plant_from_json(
+    json: &Field,
+    registry: &'static PlantRegistry,
+) -> Result<Box<dyn Plant>, LoadError> {
+    let plant_type = json.get("type");
+    registry
+        .id(plant_type.str()?)
+        .and_then(|id| registry.get(id))
+        .ok_or_else(|| plant_type.invalid("a plant type"))?
+        .load_plant(json)
 }
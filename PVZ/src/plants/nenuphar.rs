@@ -3,25 +3,41 @@ use std::time::Duration;
 use anyhow::Result;
 use red_sdl::refs::Ref;
 use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
 
-use crate::{projectile::Projectile, sun::Sun, zombie::Zombie, State};
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::Projectile,
+    sun::Sun,
+    zombie::Zombie,
+    State,
+};
 
-use super::Plant;
+use super::{registry::PlantDefinition, Plant};
 
 #[derive(Clone)]
 pub struct Nenuphar {
+    definition: &'static PlantDefinition,
     health: Duration,
 }
 impl Nenuphar {
-    pub const fn new() -> Self {
+    pub const fn new(definition: &'static PlantDefinition) -> Self {
         Self {
-            health: Duration::new(3, 0),
+            definition,
+            health: definition.health,
         }
     }
+
+    pub fn load(definition: &'static PlantDefinition, json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            definition,
+            health: json.get("health_ns").duration()?,
+        })
+    }
 }
 impl Plant for Nenuphar {
     fn texture(&self, state: Ref<State>) -> &'static Texture {
-        state.as_ref().textures().plant_nenuphar()
+        state.as_ref().textures().plant(self.definition.id, 0)
     }
 
     fn rect(&self, x: f32, y: f32) -> FRect {
@@ -33,18 +49,18 @@ impl Plant for Nenuphar {
     }
 
     fn cost(&self) -> u32 {
-        25
+        self.definition.cost
     }
 
     fn clone(&self) -> Box<dyn Plant> {
         Box::new(Clone::clone(self))
     }
 
-    fn can_go_in_water(&self) -> bool {
-        true
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
     }
 
-    fn is_nenuphar(&self) -> bool {
+    fn blocks_zombies(&self) -> bool {
         true
     }
 
@@ -62,4 +78,11 @@ impl Plant for Nenuphar {
     fn health(&mut self) -> &mut Duration {
         &mut self.health
     }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "health_ns": duration_value(self.health),
+        })
+    }
 }
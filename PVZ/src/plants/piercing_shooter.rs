@@ -0,0 +1,50 @@
+use crate::{
+    projectile::{Pierce, Piercing, Projectile},
+    zombie::{reachable, Zombie},
+};
+
+use super::{registry::PlantDefinition, shooter::Shot};
+
+#[derive(Clone)]
+pub struct PiercingShot {
+    pub pierce: Pierce,
+}
+impl Shot for PiercingShot {
+    fn in_range(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> bool {
+        zombies[y_pos].iter().any(|zombie| {
+            let hit_box = zombie.hit_box(0.);
+            reachable(zombie.as_ref(), definition.anti_air)
+                && hit_box.right() > x
+                && self
+                    .pierce
+                    .range
+                    .is_none_or(|range| hit_box.left() < x + range)
+        })
+    }
+
+    fn fire(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        _: f32,
+        y_pos: usize,
+        _: &[Vec<Box<dyn Zombie>>],
+    ) -> Vec<(usize, Box<dyn Projectile>)> {
+        vec![(
+            y_pos,
+            Box::new(Piercing {
+                definition,
+                pierce: self.pierce,
+                start_x: x - 25. / 1280.,
+                x: x - 25. / 1280.,
+                hits: Vec::new(),
+            }),
+        )]
+    }
+}
@@ -0,0 +1,395 @@
+use std::time::Duration;
+
+use crate::{
+    level::{
+        config::RowType,
+        parse::{read_json, Field, LoadError},
+    },
+    projectile::{DamageType, Lob, Pierce},
+};
+
+use super::{
+    chomper::Chomper,
+    explosive::{Explosion, Explosive},
+    homing_shooter::HomingShot,
+    lobber::LobShot,
+    nenuphar::Nenuphar,
+    peashooter::PeaShot,
+    piercing_shooter::PiercingShot,
+    shooter::Shooter,
+    spikes::Spikes,
+    split_peashooter::SplitShot,
+    star_shooter::StarShot,
+    sunflower::Sunflower,
+    torch::Torch,
+    trap::Trap,
+    triple_peashooter::TripleShot,
+    wall::Wall,
+    Plant,
+};
+
+#[derive(Clone, Copy)]
+pub enum PlantKind {
+    Nenuphar,
+    Sunflower {
+        interval: Duration,
+        initial_charge: Duration,
+    },
+    Shooter {
+        damage_type: DamageType,
+        interval: Duration,
+    },
+    TripleShooter {
+        damage_type: DamageType,
+        interval: Duration,
+    },
+    Wall,
+    Explosive(Explosion),
+    Trap {
+        arming: Duration,
+        damage_amount: usize,
+    },
+    Spikes {
+        interval: Duration,
+        damage_amount: usize,
+    },
+    Chomper {
+        digest: Duration,
+    },
+    Lobber {
+        interval: Duration,
+        lob: Lob,
+    },
+    PiercingShooter {
+        interval: Duration,
+        pierce: Pierce,
+    },
+    Torch {
+        damage_boost: usize,
+    },
+    SplitShooter {
+        damage_type: DamageType,
+        interval: Duration,
+    },
+    StarShooter {
+        interval: Duration,
+        damage_amount: usize,
+    },
+    HomingShooter {
+        interval: Duration,
+        damage_amount: usize,
+    },
+}
+
+impl PlantKind {
+    fn load(json: &Field) -> Result<Self, LoadError> {
+        let kind = json.get("kind");
+        Ok(match kind.str()? {
+            "nenuphar" => PlantKind::Nenuphar,
+            "sunflower" => PlantKind::Sunflower {
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+                initial_charge: Duration::from_millis(json.get("initial_charge_ms").u64()?),
+            },
+            "shooter" => PlantKind::Shooter {
+                damage_type: DamageType::load(&json.get("damage_type"))?,
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+            },
+            "triple_shooter" => PlantKind::TripleShooter {
+                damage_type: DamageType::load(&json.get("damage_type"))?,
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+            },
+            "wall" => PlantKind::Wall,
+            "explosive" => PlantKind::Explosive(Explosion::load(json)?),
+            "trap" => PlantKind::Trap {
+                arming: Duration::from_millis(json.get("arming_ms").u64()?),
+                damage_amount: json.get("damage").u64()? as usize,
+            },
+            "spikes" => PlantKind::Spikes {
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+                damage_amount: json.get("damage").u64()? as usize,
+            },
+            "chomper" => PlantKind::Chomper {
+                digest: Duration::from_millis(json.get("digest_ms").u64()?),
+            },
+            "lobber" => PlantKind::Lobber {
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+                lob: Lob::load(json)?,
+            },
+            "piercing_shooter" => PlantKind::PiercingShooter {
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+                pierce: Pierce::load(json)?,
+            },
+            "torch" => PlantKind::Torch {
+                damage_boost: json.get("damage_boost").u64()? as usize,
+            },
+            "split_shooter" => PlantKind::SplitShooter {
+                damage_type: DamageType::load(&json.get("damage_type"))?,
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+            },
+            "star_shooter" => PlantKind::StarShooter {
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+                damage_amount: json.get("damage").u64()? as usize,
+            },
+            "homing_shooter" => PlantKind::HomingShooter {
+                interval: Duration::from_millis(json.get("interval_ms").u64()?),
+                damage_amount: json.get("damage").u64()? as usize,
+            },
+            _ => return Err(kind.invalid("a plant kind")),
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Base,
+    Main,
+    Armor,
+}
+
+impl Layer {
+    fn load(json: &Field) -> Result<Self, LoadError> {
+        match json.str()? {
+            "base" => Ok(Layer::Base),
+            "main" => Ok(Layer::Main),
+            "armor" => Ok(Layer::Armor),
+            _ => Err(json.invalid("a plant layer")),
+        }
+    }
+}
+
+pub struct PlantDefinition {
+    pub id: u8,
+    pub name: String,
+    pub cost: u32,
+    pub health: Duration,
+    pub recharge: Duration,
+    pub textures: Vec<String>,
+    pub layer: Layer,
+    pub rows: Vec<RowType>,
+    pub tall: bool,
+    pub anti_air: bool,
+    pub kind: PlantKind,
+}
+
+impl PlantDefinition {
+    fn load(id: u8, json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            id,
+            name: json.get("name").str()?.to_owned(),
+            cost: json.get("cost").u32()?,
+            health: Duration::from_millis(json.get("health_ms").u64()?),
+            recharge: Duration::from_millis(json.get("recharge_ms").u64()?),
+            textures: {
+                let textures = json
+                    .get("textures")
+                    .items()?
+                    .iter()
+                    .map(|texture| texture.str().map(str::to_owned))
+                    .collect::<Result<Vec<String>, LoadError>>()?;
+                if textures.is_empty() {
+                    return Err(json.get("textures").invalid("at least one texture"));
+                }
+                textures
+            },
+            layer: json
+                .get("layer")
+                .optional()
+                .map(Layer::load)
+                .transpose()?
+                .unwrap_or(Layer::Main),
+            rows: match json.get("rows").optional() {
+                Some(rows) => rows
+                    .items()?
+                    .iter()
+                    .map(RowType::load)
+                    .collect::<Result<Vec<RowType>, LoadError>>()?,
+                None => vec![RowType::Grass],
+            },
+            tall: json
+                .get("tall")
+                .optional()
+                .map(Field::bool)
+                .transpose()?
+                .unwrap_or(false),
+            anti_air: json
+                .get("anti_air")
+                .optional()
+                .map(Field::bool)
+                .transpose()?
+                .unwrap_or(false),
+            kind: PlantKind::load(&json.get("behaviour"))?,
+        })
+    }
+
+    pub fn spawn(&'static self) -> Box<dyn Plant> {
+        match self.kind {
+            PlantKind::Nenuphar => Box::new(Nenuphar::new(self)),
+            PlantKind::Sunflower {
+                interval,
+                initial_charge,
+            } => Box::new(Sunflower::new(self, interval, initial_charge)),
+            PlantKind::Shooter {
+                damage_type,
+                interval,
+            } => Box::new(Shooter::new(self, interval, PeaShot { damage_type })),
+            PlantKind::TripleShooter {
+                damage_type,
+                interval,
+            } => Box::new(Shooter::new(self, interval, TripleShot { damage_type })),
+            PlantKind::Wall => Box::new(Wall::new(self)),
+            PlantKind::Explosive(explosion) => Box::new(Explosive::new(self, explosion)),
+            PlantKind::Trap {
+                arming,
+                damage_amount,
+            } => Box::new(Trap::new(self, arming, damage_amount)),
+            PlantKind::Spikes {
+                interval,
+                damage_amount,
+            } => Box::new(Spikes::new(self, interval, damage_amount)),
+            PlantKind::Chomper { digest } => Box::new(Chomper::new(self, digest)),
+            PlantKind::Lobber { interval, lob } => {
+                Box::new(Shooter::new(self, interval, LobShot { lob }))
+            }
+            PlantKind::PiercingShooter { interval, pierce } => {
+                Box::new(Shooter::new(self, interval, PiercingShot { pierce }))
+            }
+            PlantKind::Torch { damage_boost } => Box::new(Torch::new(self, damage_boost)),
+            PlantKind::SplitShooter {
+                damage_type,
+                interval,
+            } => Box::new(Shooter::new(self, interval, SplitShot { damage_type })),
+            PlantKind::StarShooter {
+                interval,
+                damage_amount,
+            } => Box::new(Shooter::new(self, interval, StarShot { damage_amount })),
+            PlantKind::HomingShooter {
+                interval,
+                damage_amount,
+            } => Box::new(Shooter::new(self, interval, HomingShot { damage_amount })),
+        }
+    }
+
+    pub fn load_plant(&'static self, json: &Field) -> Result<Box<dyn Plant>, LoadError> {
+        Ok(match self.kind {
+            PlantKind::Nenuphar => Box::new(Nenuphar::load(self, json)?),
+            PlantKind::Sunflower { interval, .. } => {
+                Box::new(Sunflower::load(self, interval, json)?)
+            }
+            PlantKind::Shooter {
+                damage_type,
+                interval,
+            } => Box::new(Shooter::load(
+                self,
+                interval,
+                PeaShot { damage_type },
+                json,
+            )?),
+            PlantKind::TripleShooter {
+                damage_type,
+                interval,
+            } => Box::new(Shooter::load(
+                self,
+                interval,
+                TripleShot { damage_type },
+                json,
+            )?),
+            PlantKind::Wall => Box::new(Wall::load(self, json)?),
+            PlantKind::Explosive(explosion) => Box::new(Explosive::load(self, explosion, json)?),
+            PlantKind::Trap { damage_amount, .. } => {
+                Box::new(Trap::load(self, damage_amount, json)?)
+            }
+            PlantKind::Spikes {
+                interval,
+                damage_amount,
+            } => Box::new(Spikes::load(self, interval, damage_amount, json)?),
+            PlantKind::Chomper { digest } => Box::new(Chomper::load(self, digest, json)?),
+            PlantKind::Lobber { interval, lob } => {
+                Box::new(Shooter::load(self, interval, LobShot { lob }, json)?)
+            }
+            PlantKind::PiercingShooter { interval, pierce } => Box::new(Shooter::load(
+                self,
+                interval,
+                PiercingShot { pierce },
+                json,
+            )?),
+            PlantKind::Torch { damage_boost } => Box::new(Torch::load(self, damage_boost, json)?),
+            PlantKind::SplitShooter {
+                damage_type,
+                interval,
+            } => Box::new(Shooter::load(
+                self,
+                interval,
+                SplitShot { damage_type },
+                json,
+            )?),
+            PlantKind::StarShooter {
+                interval,
+                damage_amount,
+            } => Box::new(Shooter::load(
+                self,
+                interval,
+                StarShot { damage_amount },
+                json,
+            )?),
+            PlantKind::HomingShooter {
+                interval,
+                damage_amount,
+            } => Box::new(Shooter::load(
+                self,
+                interval,
+                HomingShot { damage_amount },
+                json,
+            )?),
+        })
+    }
+}
+
+pub struct PlantRegistry {
+    pub plants: Vec<PlantDefinition>,
+}
+
+impl PlantRegistry {
+    pub fn load() -> Result<Self, LoadError> {
+        let file = "assets/plants.json";
+        let json = read_json(file)?;
+        let json = Field::root(file, &json);
+        let version = json.get("version");
+        if version.u64()? != 1 {
+            return Err(version.invalid("format version 1"));
+        }
+        let plants = json.get("plants").items()?;
+        if plants.len() > u8::MAX as usize {
+            return Err(json.get("plants").invalid("at most 255 plants"));
+        }
+        Ok(Self {
+            plants: plants
+                .iter()
+                .enumerate()
+                .map(|(id, plant)| PlantDefinition::load(id as u8, plant))
+                .collect::<Result<Vec<PlantDefinition>, LoadError>>()?,
+        })
+    }
+
+    pub fn get(&self, id: u8) -> Option<&PlantDefinition> {
+        self.plants.get(id as usize)
+    }
+
+    pub fn id(&self, name: &str) -> Option<u8> {
+        self.plants
+            .iter()
+            .find(|plant| plant.name == name)
+            .map(|plant| plant.id)
+    }
+
+    pub fn name(&self, id: u8) -> Option<&str> {
+        self.get(id).map(|plant| plant.name.as_str())
+    }
+
+    pub fn ids(&self) -> Vec<u8> {
+        self.plants.iter().map(|plant| plant.id).collect()
+    }
+
+    pub fn spawn(&'static self, id: u8) -> Box<dyn Plant> {
+        self.plants[id as usize].spawn()
+    }
+}
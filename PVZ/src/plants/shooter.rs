@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use red_sdl::refs::Ref;
+use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
+
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::Projectile,
+    sun::Sun,
+    zombie::Zombie,
+    State,
+};
+
+use super::{registry::PlantDefinition, Plant};
+
+pub trait Shot: Clone + 'static {
+    fn in_range(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> bool;
+    fn fire(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        y: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> Vec<(usize, Box<dyn Projectile>)>;
+}
+
+#[derive(Clone)]
+pub struct Shooter<S: Shot> {
+    definition: &'static PlantDefinition,
+    interval: Duration,
+    charge: Duration,
+    shot: S,
+    health: Duration,
+}
+impl<S: Shot> Shooter<S> {
+    pub const fn new(definition: &'static PlantDefinition, interval: Duration, shot: S) -> Self {
+        Shooter {
+            definition,
+            interval,
+            charge: Duration::ZERO,
+            shot,
+            health: definition.health,
+        }
+    }
+
+    pub fn load(
+        definition: &'static PlantDefinition,
+        interval: Duration,
+        shot: S,
+        json: &Field,
+    ) -> Result<Self, LoadError> {
+        Ok(Shooter {
+            definition,
+            interval,
+            charge: json.get("charge_ns").duration()?,
+            shot,
+            health: json.get("health_ns").duration()?,
+        })
+    }
+}
+impl<S: Shot> Plant for Shooter<S> {
+    fn texture(&self, state: Ref<State>) -> &'static Texture {
+        state.as_ref().textures().plant(self.definition.id, 0)
+    }
+
+    fn rect(&self, x: f32, y: f32) -> FRect {
+        FRect::new(x, y, 70. / 1280., 100. / 720.)
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        self.charge += elapsed;
+        Ok(())
+    }
+
+    fn cost(&self) -> u32 {
+        self.definition.cost
+    }
+
+    fn clone(&self) -> Box<dyn Plant> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
+    }
+
+    fn blocks_zombies(&self) -> bool {
+        true
+    }
+
+    fn health(&mut self) -> &mut Duration {
+        &mut self.health
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "charge_ns": duration_value(self.charge),
+            "health_ns": duration_value(self.health),
+        })
+    }
+
+    fn should_spawn(
+        &mut self,
+        x: f32,
+        y: f32,
+        y_pos: usize,
+        _: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
+        if !self.shot.in_range(self.definition, x, y_pos, zombies) {
+            self.charge = self.charge.clamp(Duration::ZERO, self.interval)
+        } else if self.charge >= self.interval {
+            self.charge -= self.interval;
+            return (
+                Vec::new(),
+                self.shot.fire(self.definition, x, y, y_pos, zombies),
+            );
+        }
+        (Vec::new(), Vec::new())
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use red_sdl::refs::Ref;
+use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
+
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::{DamageType, Projectile},
+    sun::Sun,
+    zombie::{Hit, HitSource, Zombie},
+    State,
+};
+
+use super::{in_tile, registry::PlantDefinition, Plant};
+
+#[derive(Clone)]
+pub struct Spikes {
+    definition: &'static PlantDefinition,
+    interval: Duration,
+    damage_amount: usize,
+    charge: Duration,
+    health: Duration,
+}
+impl Spikes {
+    pub const fn new(
+        definition: &'static PlantDefinition,
+        interval: Duration,
+        damage_amount: usize,
+    ) -> Self {
+        Self {
+            definition,
+            interval,
+            damage_amount,
+            charge: Duration::ZERO,
+            health: definition.health,
+        }
+    }
+
+    pub fn load(
+        definition: &'static PlantDefinition,
+        interval: Duration,
+        damage_amount: usize,
+        json: &Field,
+    ) -> Result<Self, LoadError> {
+        Ok(Self {
+            definition,
+            interval,
+            damage_amount,
+            charge: json.get("charge_ns").duration()?,
+            health: json.get("health_ns").duration()?,
+        })
+    }
+}
+impl Plant for Spikes {
+    fn texture(&self, state: Ref<State>) -> &'static Texture {
+        state.as_ref().textures().plant(self.definition.id, 0)
+    }
+
+    fn rect(&self, x: f32, y: f32) -> FRect {
+        FRect::new(x, y, 70. / 1280., 100. / 720.)
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        self.charge += elapsed;
+        Ok(())
+    }
+
+    fn cost(&self) -> u32 {
+        self.definition.cost
+    }
+
+    fn clone(&self) -> Box<dyn Plant> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
+    }
+
+    fn blocks_zombies(&self) -> bool {
+        false
+    }
+
+    fn should_spawn(
+        &mut self,
+        _: f32,
+        _: f32,
+        _: usize,
+        _: usize,
+        _: &[Vec<Box<dyn Zombie>>],
+    ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
+        (Vec::new(), Vec::new())
+    }
+
+    fn act_on_tile(&mut self, left: f32, right: f32, zombies: &mut Vec<Box<dyn Zombie>>) -> bool {
+        if !zombies
+            .iter()
+            .any(|zombie| in_tile(zombie.as_ref(), left, right))
+        {
+            self.charge = self.charge.clamp(Duration::ZERO, self.interval);
+        } else if self.charge >= self.interval {
+            self.charge -= self.interval;
+            zombies.retain_mut(|zombie| {
+                !in_tile(zombie.as_ref(), left, right)
+                    || !zombie
+                        .hit(Hit::new(
+                            self.damage_amount,
+                            DamageType::Normal,
+                            HitSource::Area,
+                        ))
+                        .0
+            });
+        }
+        false
+    }
+
+    fn health(&mut self) -> &mut Duration {
+        &mut self.health
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "charge_ns": duration_value(self.charge),
+            "health_ns": duration_value(self.health),
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    projectile::{DamageType, Pea, Projectile},
+    zombie::{reachable, Zombie},
+};
+
+use super::{registry::PlantDefinition, shooter::Shot};
+
+#[derive(Clone)]
+pub struct SplitShot {
+    pub damage_type: DamageType,
+}
+impl Shot for SplitShot {
+    fn in_range(
+        &self,
+        definition: &'static PlantDefinition,
+        _: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> bool {
+        zombies[y_pos]
+            .iter()
+            .any(|zombie| reachable(zombie.as_ref(), definition.anti_air))
+    }
+
+    fn fire(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        _: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> Vec<(usize, Box<dyn Projectile>)> {
+        let zombies = zombies[y_pos]
+            .iter()
+            .filter(|zombie| reachable(zombie.as_ref(), definition.anti_air));
+        let ahead = zombies.clone().any(|zombie| zombie.hit_box(0.).right() > x);
+        let behind = zombies.clone().any(|zombie| zombie.hit_box(0.).left() < x);
+        let mut projectiles: Vec<(usize, Box<dyn Projectile>)> = Vec::new();
+        if ahead {
+            projectiles.push((y_pos, Box::new(Pea::new(x - 25. / 1280., self.damage_type))));
+        }
+        if behind {
+            projectiles.push((
+                y_pos,
+                Box::new(Pea::backward(x - 25. / 1280., self.damage_type)),
+            ));
+            projectiles.push((
+                y_pos,
+                Box::new(Pea::backward(x - 50. / 1280., self.damage_type)),
+            ));
+        }
+        projectiles
+    }
+}
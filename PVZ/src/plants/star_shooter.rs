@@ -0,0 +1,58 @@
+use crate::{
+    projectile::{Projectile, Star},
+    zombie::{reachable, Zombie},
+};
+
+use super::{registry::PlantDefinition, shooter::Shot};
+
+#[derive(Clone)]
+pub struct StarShot {
+    pub damage_amount: usize,
+}
+impl Shot for StarShot {
+    fn in_range(
+        &self,
+        definition: &'static PlantDefinition,
+        _: f32,
+        _: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> bool {
+        zombies
+            .iter()
+            .flatten()
+            .any(|zombie| reachable(zombie.as_ref(), definition.anti_air))
+    }
+
+    fn fire(
+        &self,
+        definition: &'static PlantDefinition,
+        x: f32,
+        y: f32,
+        y_pos: usize,
+        _: &[Vec<Box<dyn Zombie>>],
+    ) -> Vec<(usize, Box<dyn Projectile>)> {
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        [
+            (-1., 0.),
+            (0., -1.),
+            (0., 1.),
+            (diagonal, -diagonal),
+            (diagonal, diagonal),
+        ]
+        .into_iter()
+        .map(|direction| {
+            (
+                y_pos,
+                Box::new(Star {
+                    definition,
+                    damage_amount: self.damage_amount,
+                    x: x - 25. / 1280.,
+                    y: y + 25. / 720.,
+                    direction,
+                    target: None,
+                }) as Box<dyn Projectile>,
+            )
+        })
+        .collect()
+    }
+}
@@ -3,27 +3,55 @@ use std::time::Duration;
 use anyhow::Result;
 use red_sdl::refs::Ref;
 use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
 
-use crate::{projectile::Projectile, sun::Sun, zombie::Zombie, State};
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::Projectile,
+    sun::Sun,
+    zombie::Zombie,
+    State,
+};
 
-use super::Plant;
+use super::{registry::PlantDefinition, Plant};
 
 #[derive(Clone)]
 pub struct Sunflower {
+    definition: &'static PlantDefinition,
+    interval: Duration,
     charge: Duration,
     health: Duration,
 }
 impl Sunflower {
-    pub const fn new() -> Self {
+    pub const fn new(
+        definition: &'static PlantDefinition,
+        interval: Duration,
+        initial_charge: Duration,
+    ) -> Self {
         Self {
-            charge: Duration::new(19, 0),
-            health: Duration::new(3, 0),
+            definition,
+            interval,
+            charge: initial_charge,
+            health: definition.health,
         }
     }
+
+    pub fn load(
+        definition: &'static PlantDefinition,
+        interval: Duration,
+        json: &Field,
+    ) -> Result<Self, LoadError> {
+        Ok(Self {
+            definition,
+            interval,
+            charge: json.get("charge_ns").duration()?,
+            health: json.get("health_ns").duration()?,
+        })
+    }
 }
 impl Plant for Sunflower {
     fn texture(&self, state: Ref<State>) -> &'static Texture {
-        state.as_ref().textures().plant_sunflower()
+        state.as_ref().textures().plant(self.definition.id, 0)
     }
 
     fn rect(&self, x: f32, y: f32) -> FRect {
@@ -36,25 +64,33 @@ impl Plant for Sunflower {
     }
 
     fn cost(&self) -> u32 {
-        50
+        self.definition.cost
     }
 
     fn clone(&self) -> Box<dyn Plant> {
         Box::new(Clone::clone(self))
     }
 
-    fn can_go_in_water(&self) -> bool {
-        false
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
     }
 
-    fn is_nenuphar(&self) -> bool {
-        false
+    fn blocks_zombies(&self) -> bool {
+        true
     }
 
     fn health(&mut self) -> &mut Duration {
         &mut self.health
     }
 
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "charge_ns": duration_value(self.charge),
+            "health_ns": duration_value(self.health),
+        })
+    }
+
     fn should_spawn(
         &mut self,
         x: f32,
@@ -63,8 +99,8 @@ impl Plant for Sunflower {
         _: usize,
         _: &[Vec<Box<dyn Zombie>>],
     ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
-        if self.charge >= Duration::from_millis(24000) {
-            self.charge -= Duration::from_millis(24000);
+        if self.charge >= self.interval {
+            self.charge -= self.interval;
             return (
                 vec![Sun::new(x, y - 50. / 720., y + 50. / 720.)],
                 Vec::new(),
@@ -0,0 +1,49 @@
+use crate::level::config::RowType;
+
+use super::{
+    registry::{Layer, PlantDefinition},
+    Plant,
+};
+
+#[derive(Default)]
+pub struct Tile {
+    pub layers: [Option<Box<dyn Plant>>; 3],
+}
+
+impl Tile {
+    pub fn plants(&self) -> impl Iterator<Item = &dyn Plant> {
+        self.layers.iter().flatten().map(Box::as_ref)
+    }
+
+    pub fn can_place(&self, definition: &PlantDefinition, row: RowType) -> bool {
+        match definition.layer {
+            Layer::Base => self.plants().next().is_none() && definition.rows.contains(&row),
+            layer => {
+                self.layers[layer as usize].is_none()
+                    && (self.layers[Layer::Base as usize].is_some()
+                        || definition.rows.contains(&row))
+            }
+        }
+    }
+
+    pub fn place(&mut self, plant: Box<dyn Plant>) {
+        let layer = plant.definition().layer as usize;
+        self.layers[layer] = Some(plant);
+    }
+
+    pub fn remove_outer(&mut self) -> bool {
+        self.layers
+            .iter_mut()
+            .rev()
+            .find(|slot| slot.is_some())
+            .and_then(Option::take)
+            .is_some()
+    }
+
+    pub fn blocker(&mut self) -> Option<&mut Option<Box<dyn Plant>>> {
+        self.layers
+            .iter_mut()
+            .rev()
+            .find(|slot| slot.as_ref().is_some_and(|plant| plant.blocks_zombies()))
+    }
+}
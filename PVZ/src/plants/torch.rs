@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use red_sdl::refs::Ref;
+use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
+
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::Projectile,
+    sun::Sun,
+    zombie::Zombie,
+    State,
+};
+
+use super::{registry::PlantDefinition, Plant};
+
+#[derive(Clone)]
+pub struct Torch {
+    definition: &'static PlantDefinition,
+    damage_boost: usize,
+    health: Duration,
+}
+impl Torch {
+    pub const fn new(definition: &'static PlantDefinition, damage_boost: usize) -> Self {
+        Self {
+            definition,
+            damage_boost,
+            health: definition.health,
+        }
+    }
+
+    pub fn load(
+        definition: &'static PlantDefinition,
+        damage_boost: usize,
+        json: &Field,
+    ) -> Result<Self, LoadError> {
+        Ok(Self {
+            definition,
+            damage_boost,
+            health: json.get("health_ns").duration()?,
+        })
+    }
+}
+impl Plant for Torch {
+    fn texture(&self, state: Ref<State>) -> &'static Texture {
+        state.as_ref().textures().plant(self.definition.id, 0)
+    }
+
+    fn rect(&self, x: f32, y: f32) -> FRect {
+        FRect::new(x, y, 70. / 1280., 100. / 720.)
+    }
+
+    fn update(&mut self, _: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    fn cost(&self) -> u32 {
+        self.definition.cost
+    }
+
+    fn clone(&self) -> Box<dyn Plant> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
+    }
+
+    fn blocks_zombies(&self) -> bool {
+        true
+    }
+
+    fn should_spawn(
+        &mut self,
+        _: f32,
+        _: f32,
+        _: usize,
+        _: usize,
+        _: &[Vec<Box<dyn Zombie>>],
+    ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
+        (Vec::new(), Vec::new())
+    }
+
+    fn transform_projectile(&self, x: usize, proj: &mut dyn Projectile) {
+        proj.ignite(x, self.damage_boost);
+    }
+
+    fn health(&mut self) -> &mut Duration {
+        &mut self.health
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "health_ns": duration_value(self.health),
+        })
+    }
+}
@@ -1,103 +1,51 @@
-use std::time::Duration;
-
-use anyhow::Result;
-use red_sdl::refs::Ref;
-use sdl2::{rect::FRect, render::Texture};
+use std::ops::RangeInclusive;
 
 use crate::{
     projectile::{DamageType, Pea, Projectile},
-    sun::Sun,
-    zombie::Zombie,
-    State,
+    zombie::{reachable, Zombie},
 };
 
-use super::Plant;
+use super::{registry::PlantDefinition, shooter::Shot};
 
 #[derive(Clone)]
-pub struct PlantTriple {
-    charge: Duration,
-    health: Duration,
+pub struct TripleShot {
+    pub damage_type: DamageType,
 }
-impl PlantTriple {
-    pub const fn new() -> Self {
-        Self {
-            charge: Duration::ZERO,
-            health: Duration::new(3, 0),
-        }
+impl TripleShot {
+    fn rows(y_pos: usize, zombies: &[Vec<Box<dyn Zombie>>]) -> RangeInclusive<usize> {
+        y_pos.saturating_sub(1)..=(y_pos + 1).min(zombies.len() - 1)
     }
 }
-impl Plant for PlantTriple {
-    fn texture(&self, state: Ref<State>) -> &'static Texture {
-        state.as_ref().textures().plant_triple()
-    }
-
-    fn rect(&self, x: f32, y: f32) -> FRect {
-        FRect::new(x, y, 70. / 1280., 100. / 720.)
-    }
-
-    fn update(&mut self, elapsed: Duration) -> Result<()> {
-        self.charge += elapsed;
-        Ok(())
-    }
-
-    fn cost(&self) -> u32 {
-        325
-    }
-
-    fn clone(&self) -> Box<dyn Plant> {
-        Box::new(Clone::clone(self))
-    }
-
-    fn can_go_in_water(&self) -> bool {
-        false
-    }
-
-    fn is_nenuphar(&self) -> bool {
-        false
+impl Shot for TripleShot {
+    fn in_range(
+        &self,
+        definition: &'static PlantDefinition,
+        _: f32,
+        y_pos: usize,
+        zombies: &[Vec<Box<dyn Zombie>>],
+    ) -> bool {
+        Self::rows(y_pos, zombies).any(|y| {
+            zombies[y]
+                .iter()
+                .any(|zombie| reachable(zombie.as_ref(), definition.anti_air))
+        })
     }
 
-    fn should_spawn(
-        &mut self,
+    fn fire(
+        &self,
+        _: &'static PlantDefinition,
         x: f32,
         _: f32,
-        y: usize,
-        max_y: usize,
+        y_pos: usize,
         zombies: &[Vec<Box<dyn Zombie>>],
-    ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
-        if (y == 0 || zombies[y - 1].is_empty())
-            && zombies[y].is_empty()
-            && (y == max_y || zombies[y + 1].is_empty())
-        {
-            self.charge = self
-                .charge
-                .clamp(Duration::ZERO, Duration::from_secs_f32(1.5))
-        } else if self.charge >= Duration::from_millis(1500) {
-            self.charge -= Duration::from_millis(1500);
-            return (
-                Vec::new(),
-                if y == 0 {
-                    vec![new_pea(x, y), new_pea(x, y + 1)]
-                } else if y == max_y {
-                    vec![new_pea(x, y - 1), new_pea(x, y)]
-                } else {
-                    vec![new_pea(x, y - 1), new_pea(x, y), new_pea(x, y + 1)]
-                },
-            );
-        }
-        (Vec::new(), Vec::new())
+    ) -> Vec<(usize, Box<dyn Projectile>)> {
+        Self::rows(y_pos, zombies)
+            .map(|y| {
+                (
+                    y,
+                    Box::new(Pea::new(x - 25. / 1280., self.damage_type)) as Box<dyn Projectile>,
+                )
+            })
+            .collect()
     }
-
-    fn health(&mut self) -> &mut Duration {
-        &mut self.health
-    }
-}
-
-fn new_pea(x: f32, y: usize) -> (usize, Box<dyn Projectile>) {
-    (
-        y,
-        Box::new(Pea {
-            x: x - 25. / 1280.,
-            damage_type: DamageType::Normal,
-        }),
-    )
 }
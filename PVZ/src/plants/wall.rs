@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use red_sdl::refs::Ref;
+use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
+
+use crate::{
+    level::parse::{duration_value, Field, LoadError},
+    projectile::Projectile,
+    sun::Sun,
+    zombie::Zombie,
+    State,
+};
+
+use super::{registry::PlantDefinition, Plant};
+
+#[derive(Clone)]
+pub struct Wall {
+    definition: &'static PlantDefinition,
+    health: Duration,
+}
+impl Wall {
+    pub const fn new(definition: &'static PlantDefinition) -> Self {
+        Self {
+            definition,
+            health: definition.health,
+        }
+    }
+
+    pub fn load(definition: &'static PlantDefinition, json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            definition,
+            health: json.get("health_ns").duration()?,
+        })
+    }
+}
+impl Plant for Wall {
+    fn texture(&self, state: Ref<State>) -> &'static Texture {
+        let stages = self.definition.textures.len();
+        let damage = 1. - self.health.as_secs_f32() / self.definition.health.as_secs_f32();
+        state.as_ref().textures().plant(
+            self.definition.id,
+            ((damage * stages as f32) as usize).min(stages - 1),
+        )
+    }
+
+    fn rect(&self, x: f32, y: f32) -> FRect {
+        FRect::new(x, y, 70. / 1280., 100. / 720.)
+    }
+
+    fn update(&mut self, _: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    fn cost(&self) -> u32 {
+        self.definition.cost
+    }
+
+    fn clone(&self) -> Box<dyn Plant> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn definition(&self) -> &'static PlantDefinition {
+        self.definition
+    }
+
+    fn blocks_zombies(&self) -> bool {
+        true
+    }
+
+    fn should_spawn(
+        &mut self,
+        _: f32,
+        _: f32,
+        _: usize,
+        _: usize,
+        _: &[Vec<Box<dyn Zombie>>],
+    ) -> (Vec<Sun>, Vec<(usize, Box<dyn Projectile>)>) {
+        (Vec::new(), Vec::new())
+    }
+
+    fn health(&mut self) -> &mut Duration {
+        &mut self.health
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "health_ns": duration_value(self.health),
+        })
+    }
+}
@@ -3,8 +3,14 @@ use std::time::Duration;
 use anyhow::Result;
 use red_sdl::refs::Ref;
 use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
 
-use crate::State;
+use crate::{
+    level::parse::{duration_value, f32_value, Field, LoadError},
+    plants::registry::{PlantDefinition, PlantKind, PlantRegistry},
+    zombie::{reachable, HitSource, Zombie},
+    State,
+};
 
 #[derive(Clone, Copy)]
 pub enum DamageType {
@@ -13,19 +19,226 @@ pub enum DamageType {
     Ice,
 }
 
+impl DamageType {
+    pub const fn name(self) -> &'static str {
+        match self {
+            DamageType::Normal => "normal",
+            DamageType::Fire => "fire",
+            DamageType::Ice => "ice",
+        }
+    }
+
+    pub fn load(json: &Field) -> Result<Self, LoadError> {
+        match json.str()? {
+            "normal" => Ok(DamageType::Normal),
+            "fire" => Ok(DamageType::Fire),
+            "ice" => Ok(DamageType::Ice),
+            _ => Err(json.invalid("a damage type")),
+        }
+    }
+}
+
+pub enum Impact {
+    Contact,
+    Piercing,
+    InFlight,
+    Landing {
+        splash_radius: f32,
+        splash_damage: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+pub struct Lob {
+    pub flight: Duration,
+    pub damage_amount: usize,
+    pub damage_type: DamageType,
+    pub splash_radius: f32,
+    pub splash_damage: usize,
+}
+
+impl Lob {
+    pub fn load(json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            flight: Duration::from_millis(json.get("flight_ms").u64()?),
+            damage_amount: json.get("damage").u64()? as usize,
+            damage_type: DamageType::load(&json.get("damage_type"))?,
+            splash_radius: json.get("splash_radius").f32()? / 1280.,
+            splash_damage: json.get("splash_damage").u64()? as usize,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Pierce {
+    pub damage_amount: usize,
+    pub damage_type: DamageType,
+    pub count: Option<usize>,
+    pub range: Option<f32>,
+}
+
+impl Pierce {
+    pub fn load(json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            damage_amount: json.get("damage").u64()? as usize,
+            damage_type: DamageType::load(&json.get("damage_type"))?,
+            count: json
+                .get("pierce")
+                .optional()
+                .map(|count| count.u64().map(|count| count as usize))
+                .transpose()?,
+            range: json
+                .get("range")
+                .optional()
+                .map(|range| range.f32().map(|range| range / 1280.))
+                .transpose()?,
+        })
+    }
+}
+
 pub trait Projectile {
     fn texture(&self, state: Ref<State>) -> &'static Texture<'static>;
     fn rect(&self, y: f32) -> FRect;
     fn update(&mut self, elapsed: Duration) -> Result<()>;
 
+    fn height(&self) -> f32;
+    fn impact(&self) -> Impact;
+    fn source(&self) -> HitSource;
+    fn reaches_air(&self) -> bool;
+    fn pierce(&mut self, _uid: u64) -> bool {
+        false
+    }
+    fn ignite(&mut self, _x: usize, _damage_boost: usize) {}
+    fn target(&self) -> Option<u64> {
+        None
+    }
+    fn aim(&mut self, _x: f32, _y: f32) {}
+    fn retarget(&mut self, _zombies: &[Vec<Box<dyn Zombie>>]) {}
     fn to_remove(&self) -> bool;
     fn damage_amount(&self) -> usize;
     fn damage_type(&self) -> DamageType;
+    fn save(&self) -> Value;
+}
+
+fn launcher(
+    plant: &Field,
+    plant_registry: &'static PlantRegistry,
+) -> Result<&'static PlantDefinition, LoadError> {
+    plant_registry
+        .id(plant.str()?)
+        .and_then(|id| plant_registry.get(id))
+        .ok_or_else(|| plant.invalid("a plant type"))
+}
+
+pub fn projectile_from_json(
+    json: &Field,
+    plant_registry: &'static PlantRegistry,
+) -> Result<Box<dyn Projectile>, LoadError> {
+    let projectile_type = json.get("type");
+    match projectile_type.str()? {
+        "pea" => Ok(Box::new(Pea {
+            x: json.get("x").f32()?,
+            damage_amount: json.get("damage").u64()? as usize,
+            damage_type: DamageType::load(&json.get("damage_type"))?,
+            ignited_at: json
+                .get("ignited_at")
+                .optional()
+                .map(|x| x.u64().map(|x| x as usize))
+                .transpose()?,
+            backward: json
+                .get("backward")
+                .optional()
+                .map(|backward| backward.bool())
+                .transpose()?
+                .unwrap_or(false),
+        })),
+        "lobbed" => {
+            let plant = json.get("plant");
+            let definition = launcher(&plant, plant_registry)?;
+            let PlantKind::Lobber { lob, .. } = definition.kind else {
+                return Err(plant.invalid("a lobbing plant"));
+            };
+            Ok(Box::new(Lobbed {
+                definition,
+                lob,
+                start_x: json.get("start_x").f32()?,
+                target_x: json.get("target_x").f32()?,
+                target: json
+                    .get("target")
+                    .optional()
+                    .map(|target| target.u64())
+                    .transpose()?,
+                time: json.get("time_ns").duration()?,
+            }))
+        }
+        "piercing" => {
+            let plant = json.get("plant");
+            let definition = launcher(&plant, plant_registry)?;
+            let PlantKind::PiercingShooter { pierce, .. } = definition.kind else {
+                return Err(plant.invalid("a piercing plant"));
+            };
+            Ok(Box::new(Piercing {
+                definition,
+                pierce,
+                start_x: json.get("start_x").f32()?,
+                x: json.get("x").f32()?,
+                hits: json
+                    .get("hits")
+                    .items()?
+                    .iter()
+                    .map(Field::u64)
+                    .collect::<Result<_, LoadError>>()?,
+            }))
+        }
+        "star" => {
+            let plant = json.get("plant");
+            let definition = launcher(&plant, plant_registry)?;
+            let (PlantKind::StarShooter { damage_amount, .. }
+            | PlantKind::HomingShooter { damage_amount, .. }) = definition.kind
+            else {
+                return Err(plant.invalid("a star shooting plant"));
+            };
+            Ok(Box::new(Star {
+                definition,
+                damage_amount,
+                x: json.get("x").f32()?,
+                y: json.get("y").f32()?,
+                direction: (json.get("dx").f32()?, json.get("dy").f32()?),
+                target: json
+                    .get("target")
+                    .optional()
+                    .map(|target| target.u64())
+                    .transpose()?,
+            }))
+        }
+        _ => Err(projectile_type.invalid("a projectile type")),
+    }
 }
 
 pub struct Pea {
     pub x: f32,
+    pub damage_amount: usize,
     pub damage_type: DamageType,
+    pub ignited_at: Option<usize>,
+    pub backward: bool,
+}
+impl Pea {
+    pub const fn new(x: f32, damage_type: DamageType) -> Self {
+        Self {
+            x,
+            damage_amount: 20,
+            damage_type,
+            ignited_at: None,
+            backward: false,
+        }
+    }
+
+    pub const fn backward(x: f32, damage_type: DamageType) -> Self {
+        Self {
+            backward: true,
+            ..Self::new(x, damage_type)
+        }
+    }
 }
 impl Projectile for Pea {
     fn texture(&self, state: Ref<State>) -> &'static Texture<'static> {
@@ -42,19 +255,366 @@ impl Projectile for Pea {
     }
 
     fn update(&mut self, elapsed: Duration) -> Result<()> {
-        self.x += elapsed.as_secs_f32() * 200. / 1280.;
+        let distance = elapsed.as_secs_f32() * 200. / 1280.;
+        if self.backward {
+            self.x -= distance;
+        } else {
+            self.x += distance;
+        }
         Ok(())
     }
 
+    fn height(&self) -> f32 {
+        0.
+    }
+
+    fn impact(&self) -> Impact {
+        Impact::Contact
+    }
+
+    fn source(&self) -> HitSource {
+        if self.backward {
+            HitSource::Back
+        } else {
+            HitSource::Front
+        }
+    }
+
+    fn reaches_air(&self) -> bool {
+        false
+    }
+
+    fn ignite(&mut self, x: usize, damage_boost: usize) {
+        if self.ignited_at == Some(x) {
+            return;
+        }
+        self.ignited_at = Some(x);
+        match self.damage_type {
+            DamageType::Normal => {
+                self.damage_type = DamageType::Fire;
+                self.damage_amount += damage_boost;
+            }
+            DamageType::Fire => {}
+            DamageType::Ice => self.damage_type = DamageType::Normal,
+        }
+    }
+
     fn damage_amount(&self) -> usize {
-        20
+        self.damage_amount
     }
 
     fn damage_type(&self) -> DamageType {
         self.damage_type
     }
 
+    fn to_remove(&self) -> bool {
+        self.x > 1. + self.rect(0.).width() || self.x < -self.rect(0.).width()
+    }
+
+    fn save(&self) -> Value {
+        let mut json = json!({
+            "type": "pea",
+            "x": f32_value(self.x),
+            "damage": self.damage_amount,
+            "damage_type": self.damage_type.name(),
+        });
+        if let Some(x) = self.ignited_at {
+            json["ignited_at"] = x.into();
+        }
+        if self.backward {
+            json["backward"] = true.into();
+        }
+        json
+    }
+}
+
+pub struct Lobbed {
+    pub definition: &'static PlantDefinition,
+    pub lob: Lob,
+    pub start_x: f32,
+    pub target_x: f32,
+    pub target: Option<u64>,
+    pub time: Duration,
+}
+impl Lobbed {
+    fn progress(&self) -> f32 {
+        (self.time.as_secs_f32() / self.lob.flight.as_secs_f32()).min(1.)
+    }
+}
+impl Projectile for Lobbed {
+    fn texture(&self, state: Ref<State>) -> &'static Texture<'static> {
+        state
+            .as_ref()
+            .textures()
+            .plant(self.definition.id, self.definition.textures.len() - 1)
+    }
+
+    fn rect(&self, y: f32) -> FRect {
+        let x = self.start_x + (self.target_x - self.start_x) * self.progress();
+        FRect::new(x - 25. / 1280., y, 50. / 1280., 50. / 720.)
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        self.time += elapsed;
+        Ok(())
+    }
+
+    fn height(&self) -> f32 {
+        let progress = self.progress();
+        4. * progress * (1. - progress) * 150. / 720.
+    }
+
+    fn impact(&self) -> Impact {
+        if self.time < self.lob.flight {
+            Impact::InFlight
+        } else {
+            Impact::Landing {
+                splash_radius: self.lob.splash_radius,
+                splash_damage: self.lob.splash_damage,
+            }
+        }
+    }
+
+    fn source(&self) -> HitSource {
+        HitSource::Above
+    }
+
+    fn reaches_air(&self) -> bool {
+        false
+    }
+
+    fn target(&self) -> Option<u64> {
+        self.target
+    }
+
+    fn aim(&mut self, x: f32, _: f32) {
+        self.target_x = x;
+    }
+
+    fn retarget(&mut self, _: &[Vec<Box<dyn Zombie>>]) {
+        self.target = None;
+    }
+
+    fn damage_amount(&self) -> usize {
+        self.lob.damage_amount
+    }
+
+    fn damage_type(&self) -> DamageType {
+        self.lob.damage_type
+    }
+
+    fn to_remove(&self) -> bool {
+        false
+    }
+
+    fn save(&self) -> Value {
+        let mut json = json!({
+            "type": "lobbed",
+            "plant": self.definition.name,
+            "start_x": f32_value(self.start_x),
+            "target_x": f32_value(self.target_x),
+            "time_ns": duration_value(self.time),
+        });
+        if let Some(target) = self.target {
+            json["target"] = target.into();
+        }
+        json
+    }
+}
+
+pub struct Piercing {
+    pub definition: &'static PlantDefinition,
+    pub pierce: Pierce,
+    pub start_x: f32,
+    pub x: f32,
+    pub hits: Vec<u64>,
+}
+impl Projectile for Piercing {
+    fn texture(&self, state: Ref<State>) -> &'static Texture<'static> {
+        state
+            .as_ref()
+            .textures()
+            .plant(self.definition.id, self.definition.textures.len() - 1)
+    }
+
+    fn rect(&self, y: f32) -> FRect {
+        FRect::new(self.x, y, 50. / 1280., 50. / 720.)
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        self.x += elapsed.as_secs_f32() * 200. / 1280.;
+        Ok(())
+    }
+
+    fn height(&self) -> f32 {
+        0.
+    }
+
+    fn impact(&self) -> Impact {
+        Impact::Piercing
+    }
+
+    fn source(&self) -> HitSource {
+        HitSource::Front
+    }
+
+    fn reaches_air(&self) -> bool {
+        self.definition.anti_air
+    }
+
+    fn pierce(&mut self, uid: u64) -> bool {
+        if self.hits.contains(&uid)
+            || self
+                .pierce
+                .count
+                .is_some_and(|count| self.hits.len() >= count)
+        {
+            return false;
+        }
+        self.hits.push(uid);
+        true
+    }
+
+    fn damage_amount(&self) -> usize {
+        self.pierce.damage_amount
+    }
+
+    fn damage_type(&self) -> DamageType {
+        self.pierce.damage_type
+    }
+
     fn to_remove(&self) -> bool {
         self.x > 1. + self.rect(0.).width()
+            || self
+                .pierce
+                .range
+                .is_some_and(|range| self.x - self.start_x > range)
+            || self
+                .pierce
+                .count
+                .is_some_and(|count| self.hits.len() >= count)
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": "piercing",
+            "plant": self.definition.name,
+            "start_x": f32_value(self.start_x),
+            "x": f32_value(self.x),
+            "hits": self.hits,
+        })
+    }
+}
+
+pub fn homing_target(zombies: &[Vec<Box<dyn Zombie>>], reaches_air: bool) -> Option<u64> {
+    zombies
+        .iter()
+        .flatten()
+        .filter(|zombie| reachable(zombie.as_ref(), reaches_air))
+        .min_by(|zombie1, zombie2| {
+            zombie1
+                .hit_box(0.)
+                .left()
+                .total_cmp(&zombie2.hit_box(0.).left())
+        })
+        .map(|zombie| zombie.uid())
+}
+
+pub struct Star {
+    pub definition: &'static PlantDefinition,
+    pub damage_amount: usize,
+    pub x: f32,
+    pub y: f32,
+    pub direction: (f32, f32),
+    pub target: Option<u64>,
+}
+impl Projectile for Star {
+    fn texture(&self, state: Ref<State>) -> &'static Texture<'static> {
+        state
+            .as_ref()
+            .textures()
+            .plant(self.definition.id, self.definition.textures.len() - 1)
+    }
+
+    fn rect(&self, _: f32) -> FRect {
+        FRect::new(self.x, self.y, 50. / 1280., 50. / 720.)
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        let distance = elapsed.as_secs_f32() * 200.;
+        self.x += self.direction.0 * distance / 1280.;
+        self.y += self.direction.1 * distance / 720.;
+        Ok(())
+    }
+
+    fn height(&self) -> f32 {
+        0.
+    }
+
+    fn impact(&self) -> Impact {
+        Impact::Contact
+    }
+
+    fn source(&self) -> HitSource {
+        if self.direction.0 > 0. {
+            HitSource::Front
+        } else if self.direction.0 < 0. {
+            HitSource::Back
+        } else {
+            HitSource::Side
+        }
+    }
+
+    fn reaches_air(&self) -> bool {
+        self.definition.anti_air
+    }
+
+    fn target(&self) -> Option<u64> {
+        self.target
+    }
+
+    fn aim(&mut self, x: f32, y: f32) {
+        let rect = self.rect(0.);
+        let dx = (x - rect.x() - rect.width() / 2.) * 1280.;
+        let dy = (y - rect.y() - rect.height() / 2.) * 720.;
+        let length = (dx * dx + dy * dy).sqrt();
+        if length > 0. {
+            self.direction = (dx / length, dy / length);
+        }
+    }
+
+    fn retarget(&mut self, zombies: &[Vec<Box<dyn Zombie>>]) {
+        self.target = homing_target(zombies, self.definition.anti_air);
+    }
+
+    fn damage_amount(&self) -> usize {
+        self.damage_amount
+    }
+
+    fn damage_type(&self) -> DamageType {
+        DamageType::Normal
+    }
+
+    fn to_remove(&self) -> bool {
+        let rect = self.rect(0.);
+        self.x > 1. + rect.width()
+            || self.x < -rect.width()
+            || self.y > 1. + rect.height()
+            || self.y < -rect.height()
+    }
+
+    fn save(&self) -> Value {
+        let mut json = json!({
+            "type": "star",
+            "plant": self.definition.name,
+            "x": f32_value(self.x),
+            "y": f32_value(self.y),
+            "dx": f32_value(self.direction.0),
+            "dy": f32_value(self.direction.1),
+        });
+        if let Some(target) = self.target {
+            json["target"] = target.into();
+        }
+        json
     }
 }
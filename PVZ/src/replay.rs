@@ -0,0 +1,203 @@
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::level::simulation::Command;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Start,
+    Command(Command),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+    pub actions: Vec<Action>,
+    pub elapsed: Duration,
+}
+
+pub struct Replay {
+    pub level: u8,
+    pub seed: u64,
+    pub frames: Vec<Frame>,
+    pending: Vec<Action>,
+    next_frame: usize,
+}
+
+impl Replay {
+    pub const fn new(level: u8, seed: u64) -> Self {
+        Self {
+            level,
+            seed,
+            frames: Vec::new(),
+            pending: Vec::new(),
+            next_frame: 0,
+        }
+    }
+
+    pub fn record(&mut self, action: Action) {
+        self.pending.push(action);
+    }
+
+    pub fn record_frame(&mut self, elapsed: Duration) {
+        self.frames.push(Frame {
+            actions: std::mem::take(&mut self.pending),
+            elapsed,
+        });
+    }
+
+    pub fn next_frame(&mut self) -> Option<&Frame> {
+        let frame = self.frames.get(self.next_frame)?;
+        self.next_frame += 1;
+        Some(frame)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let mut data = fs::read(path).map_err(|e| anyhow!(e))?.into_iter();
+        let [level] = next(&mut data)?;
+        let seed = u64::from_le_bytes(next(&mut data)?);
+        let frames = (0..u32::from_le_bytes(next(&mut data)?))
+            .map(|_| {
+                let elapsed = Duration::from_nanos(u64::from_le_bytes(next(&mut data)?));
+                let [actions] = next(&mut data)?;
+                let actions = (0..actions)
+                    .map(|_| {
+                        Ok(match next(&mut data)? {
+                            [0] => Action::Start,
+                            [1] => {
+                                let [plant, x, y] = next(&mut data)?;
+                                Action::Command(Command::PlacePlant {
+                                    plant: plant.into(),
+                                    x: x.into(),
+                                    y: y.into(),
+                                })
+                            }
+                            [2] => Action::Command(Command::CollectSun(
+                                u16::from_le_bytes(next(&mut data)?).into(),
+                            )),
+                            [3] => {
+                                let [x, y] = next(&mut data)?;
+                                Action::Command(Command::RemovePlant {
+                                    x: x.into(),
+                                    y: y.into(),
+                                })
+                            }
+                            _ => return Err(anyhow!("Unknown replay action")),
+                        })
+                    })
+                    .collect::<Result<Vec<Action>>>()?;
+                Ok(Frame { actions, elapsed })
+            })
+            .collect::<Result<Vec<Frame>>>()?;
+        if data.next().is_some() {
+            return Err(anyhow!("Wrong replay format"));
+        }
+        Ok(Self {
+            frames,
+            ..Self::new(level, seed)
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| anyhow!(e))?
+            .as_secs();
+        fs::create_dir_all("replays").map_err(|e| anyhow!(e))?;
+        self.write(format!("replays/{}_{time}.data", self.level))
+    }
+
+    fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut data = Vec::with_capacity(13 + self.frames.len() * 9);
+        data.push(self.level);
+        data.extend(self.seed.to_le_bytes());
+        data.extend(narrow::<u32, _>(self.frames.len())?.to_le_bytes());
+        for frame in self.frames.iter() {
+            data.extend(narrow::<u64, _>(frame.elapsed.as_nanos())?.to_le_bytes());
+            data.push(narrow(frame.actions.len())?);
+            for action in frame.actions.iter() {
+                match action {
+                    Action::Start => data.push(0),
+                    Action::Command(Command::PlacePlant { plant, x, y }) => {
+                        data.extend([1, narrow(*plant)?, narrow(*x)?, narrow(*y)?])
+                    }
+                    Action::Command(Command::CollectSun(sun)) => {
+                        data.push(2);
+                        data.extend(narrow::<u16, _>(*sun)?.to_le_bytes());
+                    }
+                    Action::Command(Command::RemovePlant { x, y }) => {
+                        data.extend([3, narrow(*x)?, narrow(*y)?])
+                    }
+                }
+            }
+        }
+        fs::write(path, data).map_err(|e| anyhow!(e))
+    }
+}
+
+fn narrow<T: TryFrom<U>, U: Copy + std::fmt::Display>(value: U) -> Result<T> {
+    T::try_from(value).map_err(|_| anyhow!("Replay value {value} does not fit"))
+}
+
+fn next<const N: usize>(data: &mut impl Iterator<Item = u8>) -> Result<[u8; N]> {
+    let mut bytes = [0; N];
+    for byte in bytes.iter_mut() {
+        *byte = data.next().ok_or(anyhow!("Replay too short"))?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use anyhow::{anyhow, Result};
+
+    use crate::level::simulation::Command;
+
+    use super::{Action, Replay};
+
+    #[test]
+    fn saved_replay_loads_the_same_frames() -> Result<()> {
+        let mut replay = Replay::new(3, 0x0123_4567_89ab_cdef);
+        replay.record(Action::Start);
+        replay.record_frame(Duration::from_millis(16));
+        replay.record_frame(Duration::from_nanos(16_666_667));
+        replay.record(Action::Command(Command::PlacePlant {
+            plant: 2,
+            x: 8,
+            y: 4,
+        }));
+        replay.record(Action::Command(Command::CollectSun(300)));
+        replay.record(Action::Command(Command::RemovePlant { x: 8, y: 4 }));
+        replay.record_frame(Duration::from_millis(17));
+
+        let path = env::temp_dir().join("pvz_saved_replay_loads_the_same_frames.data");
+        replay.write(&path)?;
+        let loaded = Replay::load(&path);
+        fs::remove_file(&path).map_err(|e| anyhow!(e))?;
+        let mut loaded = loaded?;
+
+        assert_eq!(loaded.level, replay.level);
+        assert_eq!(loaded.seed, replay.seed);
+        assert_eq!(loaded.frames, replay.frames);
+        assert_eq!(loaded.next_frame(), replay.frames.first());
+        Ok(())
+    }
+
+    #[test]
+    fn replay_values_too_large_are_not_truncated() -> Result<()> {
+        let mut replay = Replay::new(3, 0);
+        replay.record(Action::Command(Command::CollectSun(70_000)));
+        replay.record_frame(Duration::from_millis(16));
+
+        let path = env::temp_dir().join("pvz_replay_values_too_large_are_not_truncated.data");
+        assert!(replay.write(&path).is_err());
+        assert!(!path.exists());
+        Ok(())
+    }
+}
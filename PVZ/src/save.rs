@@ -1,36 +1,96 @@
-use std::{fs, path::Path};
+use std::{collections::HashMap, fs, path::Path, time::Duration};
 
 use anyhow::{anyhow, Result};
 
-use crate::texts::Lang;
+use crate::{level::parse::ByteReader, texts::Lang};
+
+const SAVE_VERSION: u8 = 2;
+
+#[derive(Clone, Copy)]
+pub struct LevelProgress {
+    pub best_time: Duration,
+    pub remaining_lawn: u8,
+    pub stars: u8,
+}
 
 pub struct SaveFile {
     pub langage: Lang,
+    pub unlocked: u8,
+    pub levels: HashMap<u8, LevelProgress>,
+    file: String,
 }
 
 impl SaveFile {
     pub fn load() -> Result<SaveFile> {
-        if !Path::new("save.data").exists() {
-            return Ok(SaveFile { langage: Lang::EN });
-        }
-        let mut data = fs::read("save.data").map_err(|e| anyhow!(e))?;
-        let langage = match data.remove(0) {
-            0 => Lang::EN,
-            1 => Lang::FR,
-            _ => return Err(anyhow!("lang not recognized")),
+        Self::load_from("save.data".to_owned())
+    }
+
+    fn load_from(file: String) -> Result<SaveFile> {
+        let mut save = SaveFile {
+            langage: Lang::EN,
+            unlocked: 1,
+            levels: HashMap::new(),
+            file,
         };
-        Ok(SaveFile { langage })
+        if !Path::new(&save.file).exists() {
+            return Ok(save);
+        }
+        let mut data = ByteReader::open(save.file.clone())?;
+        match data.u8("a save version")? {
+            lang @ (0 | 1) if data.is_empty() => {
+                save.langage = Self::lang(lang).ok_or(anyhow!("lang not recognized"))?;
+                return Ok(save);
+            }
+            SAVE_VERSION => {}
+            version => return Err(anyhow!(data.invalid_byte(version, "a save version"))),
+        }
+        let lang = data.u8("a language")?;
+        save.langage = Self::lang(lang).ok_or_else(|| data.invalid_byte(lang, "a language"))?;
+        save.unlocked = data.u8("the unlocked levels count")?;
+        for _ in 0..data.u8("the completed levels count")? {
+            let level = data.u8("a level id")?;
+            let best_time =
+                Duration::from_millis(u32::from_le_bytes(data.bytes("a best time")?).into());
+            let remaining_lawn = data.u8("a remaining lawn")?;
+            let stars = data.u8("a stars count")?;
+            save.levels.insert(
+                level,
+                LevelProgress {
+                    best_time,
+                    remaining_lawn,
+                    stars,
+                },
+            );
+        }
+        data.end()?;
+        Ok(save)
+    }
+
+    const fn lang(lang: u8) -> Option<Lang> {
+        match lang {
+            0 => Some(Lang::EN),
+            1 => Some(Lang::FR),
+            _ => None,
+        }
     }
 
     fn save(&self) -> Result<()> {
-        fs::write(
-            "save.data",
-            [match self.langage {
-                Lang::EN => 0,
-                Lang::FR => 1,
-            }],
-        )
-        .map_err(|e| anyhow!(e))
+        let mut data = Vec::with_capacity(4 + self.levels.len() * 7);
+        data.push(SAVE_VERSION);
+        data.push(match self.langage {
+            Lang::EN => 0,
+            Lang::FR => 1,
+        });
+        data.push(self.unlocked);
+        data.push(u8::try_from(self.levels.len()).map_err(|e| anyhow!(e))?);
+        for (level, progress) in self.levels.iter() {
+            data.push(*level);
+            let best_time = u32::try_from(progress.best_time.as_millis()).unwrap_or(u32::MAX);
+            data.extend(best_time.to_le_bytes());
+            data.push(progress.remaining_lawn);
+            data.push(progress.stars);
+        }
+        fs::write(&self.file, data).map_err(|e| anyhow!(e))
     }
 
     pub const fn langage(&self) -> Lang {
@@ -48,4 +108,118 @@ impl SaveFile {
             Lang::FR => Lang::EN,
         })
     }
+
+    pub const fn is_unlocked(&self, level: u8) -> bool {
+        level < self.unlocked
+    }
+
+    pub fn progress(&self, level: u8) -> Option<&LevelProgress> {
+        self.levels.get(&level)
+    }
+
+    pub fn complete_level(&mut self, level: u8, time: Duration, remaining_lawn: u8) -> Result<()> {
+        let stars = 1 + (remaining_lawn >= 50) as u8 + (remaining_lawn >= 80) as u8;
+        let progress = self.levels.entry(level).or_insert(LevelProgress {
+            best_time: time,
+            remaining_lawn,
+            stars,
+        });
+        progress.best_time = progress.best_time.min(time);
+        progress.remaining_lawn = progress.remaining_lawn.max(remaining_lawn);
+        progress.stars = progress.stars.max(stars);
+        self.unlocked = self.unlocked.max(level.saturating_add(2));
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path, time::Duration};
+
+    use anyhow::{anyhow, Result};
+
+    use super::SaveFile;
+
+    fn temp_save(name: &str) -> Result<SaveFile> {
+        let file = env::temp_dir().join(format!("pvz_{name}.data"));
+        if file.exists() {
+            fs::remove_file(&file).map_err(|e| anyhow!(e))?;
+        }
+        SaveFile::load_from(file.to_string_lossy().into_owned())
+    }
+
+    fn remove(save: SaveFile) -> Result<()> {
+        if Path::new(&save.file).exists() {
+            fs::remove_file(save.file).map_err(|e| anyhow!(e))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn completing_a_level_unlocks_the_next_one() -> Result<()> {
+        let mut save = temp_save("completing_a_level_unlocks_the_next_one")?;
+        assert!(save.is_unlocked(0));
+        assert!(!save.is_unlocked(1));
+        save.complete_level(0, Duration::from_secs(90), 40)?;
+        assert!(save.is_unlocked(1));
+        assert!(!save.is_unlocked(2));
+        save.complete_level(3, Duration::from_secs(90), 40)?;
+        assert!(save.is_unlocked(4));
+        save.complete_level(1, Duration::from_secs(90), 40)?;
+        assert_eq!(save.unlocked, 5);
+        remove(save)
+    }
+
+    #[test]
+    fn completing_a_level_again_keeps_the_best_results() -> Result<()> {
+        let mut save = temp_save("completing_a_level_again_keeps_the_best_results")?;
+        save.complete_level(2, Duration::from_secs(90), 85)?;
+        save.complete_level(2, Duration::from_secs(120), 40)?;
+        save.complete_level(2, Duration::from_secs(75), 60)?;
+        let progress = save
+            .progress(2)
+            .copied()
+            .ok_or(anyhow!("level 2 should be completed"))?;
+        assert_eq!(progress.best_time, Duration::from_secs(75));
+        assert_eq!((progress.remaining_lawn, progress.stars), (85, 3));
+
+        let loaded = SaveFile::load_from(save.file.clone())?;
+        let progress = loaded
+            .progress(2)
+            .copied()
+            .ok_or(anyhow!("level 2 should be saved"))?;
+        assert_eq!(progress.best_time, Duration::from_secs(75));
+        assert_eq!((progress.remaining_lawn, progress.stars), (85, 3));
+        assert_eq!(loaded.unlocked, 4);
+        remove(save)
+    }
+
+    #[test]
+    fn best_time_too_long_is_saturated() -> Result<()> {
+        let mut save = temp_save("best_time_too_long_is_saturated")?;
+        save.complete_level(0, Duration::from_millis(u64::from(u32::MAX) + 5000), 40)?;
+        let loaded = SaveFile::load_from(save.file.clone())?;
+        assert_eq!(
+            loaded.progress(0).map(|p| p.best_time),
+            Some(Duration::from_millis(u32::MAX.into()))
+        );
+        remove(save)
+    }
+
+    #[test]
+    fn stars_follow_the_remaining_lawn() -> Result<()> {
+        let mut save = temp_save("stars_follow_the_remaining_lawn")?;
+        for (level, remaining_lawn, stars) in [
+            (0, 0, 1),
+            (1, 49, 1),
+            (2, 50, 2),
+            (3, 79, 2),
+            (4, 80, 3),
+            (5, 100, 3),
+        ] {
+            save.complete_level(level, Duration::from_secs(60), remaining_lawn)?;
+            assert_eq!(save.progress(level).map(|p| p.stars), Some(stars));
+        }
+        remove(save)
+    }
 }
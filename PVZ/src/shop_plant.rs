@@ -7,17 +7,23 @@ use red_sdl::{
     refs::{MutRef, Ref},
     user_control::UserControl,
 };
-use sdl2::{mouse::MouseButton, pixels::Color, rect::FRect, render::Canvas, video::Window};
+use sdl2::{
+    mouse::MouseButton,
+    pixels::Color,
+    rect::FRect,
+    render::{BlendMode, Canvas},
+    video::Window,
+};
 
-use crate::{level::Level, plants::Plant, State};
+use crate::{level::Level, State};
 
 pub struct ShopPlant {
-    action: fn(MutRef<Level>, Box<dyn Plant>, f32, f32),
+    action: fn(MutRef<Level>, usize, f32, f32),
     surface: FRect,
-    plant: Box<dyn Plant>,
+    plant: usize,
 }
 impl ShopPlant {
-    pub fn new(action: fn(MutRef<Level>, Box<dyn Plant>, f32, f32), plant: Box<dyn Plant>) -> Self {
+    pub fn new(action: fn(MutRef<Level>, usize, f32, f32), plant: usize) -> Self {
         Self {
             action,
             surface: FRect::new(0., 0., 0., 0.),
@@ -52,8 +58,7 @@ impl UserControl<Level, State> for ShopPlant {
                 y,
                 ..
             } if event.hover(this.surface) => {
-                let p = this.plant.clone();
-                (this.action)(parent, p, x, y);
+                (this.action)(parent, this.plant, x, y);
             }
             _ => {}
         }
@@ -76,7 +81,8 @@ impl UserControl<Level, State> for ShopPlant {
         parent: Ref<Level>,
         state: Ref<State>,
     ) -> Result<()> {
-        canvas.set_draw_color(if parent.money >= this.plant.cost() {
+        let plant = parent.as_ref().sim.shop_plants[this.plant].as_ref();
+        canvas.set_draw_color(if parent.sim.money >= plant.cost() {
             Color::RGB(0, 150, 0)
         } else {
             Color::RGB(150, 0, 0)
@@ -84,7 +90,7 @@ impl UserControl<Level, State> for ShopPlant {
         canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
         canvas
             .copy_f(
-                this.as_ref().plant.texture(state),
+                plant.texture(state),
                 None,
                 FRect::new(
                     this.surface.x(),
@@ -94,14 +100,28 @@ impl UserControl<Level, State> for ShopPlant {
                 ),
             )
             .map_err(|e| anyhow!(e))?;
+        let recharge = parent.sim.recharge_ratio(this.plant);
+        if recharge > 0. {
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(Color::RGBA(0, 0, 0, 150));
+            canvas
+                .fill_frect(FRect::new(
+                    this.surface.x(),
+                    this.surface.y(),
+                    this.surface.width(),
+                    this.surface.height() * recharge,
+                ))
+                .map_err(|e| anyhow!(e))?;
+            canvas.set_blend_mode(BlendMode::None);
+        }
         let mut text = UIString::new(
             state.as_ref().textures().font(),
-            format!("{}$", this.plant.cost()),
+            format!("{}$", plant.cost()),
         )?;
         if text.is_none() {
             text = UIString::new(
                 state.as_ref().textures().font(),
-                format!("{}$", this.plant.cost()),
+                format!("{}$", plant.cost()),
             )?;
         }
         text.ok_or(anyhow!("can't draw money"))?.draw(
@@ -3,8 +3,12 @@ use std::time::Duration;
 use anyhow::Result;
 use red_sdl::refs::Ref;
 use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
 
-use crate::State;
+use crate::{
+    level::parse::{f32_value, Field, LoadError},
+    State,
+};
 
 pub struct Sun {
     pub x: f32,
@@ -25,6 +29,22 @@ impl Sun {
         FRect::new(self.x, self.y, 60. / 1280., 90. / 720.)
     }
 
+    pub fn load(json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            x: json.get("x").f32()?,
+            y: json.get("y").f32()?,
+            dist: json.get("dist").f32()?,
+        })
+    }
+
+    pub fn save(&self) -> Value {
+        json!({
+            "x": f32_value(self.x),
+            "y": f32_value(self.y),
+            "dist": f32_value(self.dist),
+        })
+    }
+
     pub fn update(&mut self, elapsed: Duration) -> Result<()> {
         self.y = (self.y + elapsed.as_secs_f32() * 34.642944 / 720.).min(self.dist);
         Ok(())
@@ -16,8 +16,11 @@ pub struct Texts {
     pub update_available: UIString,
     pub up_to_date: UIString,
     pub loading: UIString,
+    pub invalid_level: UIString,
 
     pub save: UIString,
+    pub resume: UIString,
+    pub shovel: UIString,
 }
 
 #[derive(Clone, Copy)]
@@ -40,7 +43,10 @@ pub fn load_texts(mut state: MutRef<State>) {
         update_available: UIString::new_const(font, "An update is available."),
         up_to_date: UIString::new_const(font, "You are up to date."),
         loading: UIString::new_const(font, "Loading..."),
+        invalid_level: UIString::new_const(font, "This level file is invalid."),
         save: UIString::new_const(font, "Save"),
+        resume: UIString::new_const(font, "Resume"),
+        shovel: UIString::new_const(font, "Shovel"),
     };
     state.fr = Texts {
         lang: UIString::new_const(font, "Français"),
@@ -54,6 +60,9 @@ pub fn load_texts(mut state: MutRef<State>) {
         update_available: UIString::new_const(font, "Une mise à jour est disponible."),
         up_to_date: UIString::new_const(font, "Vous êtes à jour."),
         loading: UIString::new_const(font, "Chargement..."),
+        invalid_level: UIString::new_const(font, "Ce fichier de niveau est invalide."),
         save: UIString::new_const(font, "Sauvegarder"),
+        resume: UIString::new_const(font, "Reprendre"),
+        shovel: UIString::new_const(font, "Pelle"),
     };
 }
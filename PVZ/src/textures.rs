@@ -7,23 +7,13 @@ use sdl2::{
 };
 use std::fs;
 
+use crate::{plants::registry::PlantRegistry, zombie::registry::ZombieRegistry};
+
 pub struct Textures {
     maps: Vec<Texture<'static>>,
     sun: Texture<'static>,
-    plant_sunflower: Texture<'static>,
-    plant_simple: Texture<'static>,
-    plant_fire_simple: Texture<'static>,
-    plant_ice_simple: Texture<'static>,
-    plant_triple: Texture<'static>,
-    plant_nenuphar: Texture<'static>,
-    zombie_simple: Texture<'static>,
-    zombie_simple_1: Texture<'static>,
-    zombie_cone: Texture<'static>,
-    zombie_cone_1: Texture<'static>,
-    zombie_freeze_simple: Texture<'static>,
-    zombie_freeze_simple_1: Texture<'static>,
-    zombie_freeze_cone: Texture<'static>,
-    zombie_freeze_cone_1: Texture<'static>,
+    plants: Vec<Vec<Texture<'static>>>,
+    zombies: Vec<Vec<(Texture<'static>, Texture<'static>)>>,
     pea: Texture<'static>,
     fire_pea: Texture<'static>,
     ice_pea: Texture<'static>,
@@ -62,6 +52,8 @@ fn freezed(
 pub fn load_textures(
     canvas: &mut Canvas<Window>,
     texture_creator: &'static TextureCreator<WindowContext>,
+    zombie_registry: &ZombieRegistry,
+    plant_registry: &PlantRegistry,
 ) -> Result<Textures> {
     let maps_count = fs::read_dir("assets/maps").map_err(|e| anyhow!(e))?.count() / 2;
     let maps: Vec<Texture<'_>> = (0..maps_count)
@@ -72,31 +64,49 @@ pub fn load_textures(
     }
     let font_context = Box::leak(Box::new(ttf::init().map_err(|e| anyhow!(e))?));
 
-    let zombie_simple = texture_creator
-        .load_texture("assets/Zombies/Simple.png")
-        .map_err(|e| anyhow!(e))?;
-    let zombie_simple_1 = texture_creator
-        .load_texture("assets/Zombies/Simple_1.png")
-        .map_err(|e| anyhow!(e))?;
-    let zombie_cone = texture_creator
-        .load_texture("assets/Zombies/Cone.png")
-        .map_err(|e| anyhow!(e))?;
-    let zombie_cone_1 = texture_creator
-        .load_texture("assets/Zombies/Cone_1.png")
-        .map_err(|e| anyhow!(e))?;
-    let zombie_freeze_simple = freezed(canvas, texture_creator, &zombie_simple)?;
-    let zombie_freeze_simple_1 = freezed(canvas, texture_creator, &zombie_simple_1)?;
-    let zombie_freeze_cone = freezed(canvas, texture_creator, &zombie_cone)?;
-    let zombie_freeze_cone_1 = freezed(canvas, texture_creator, &zombie_cone_1)?;
+    let plants = plant_registry
+        .plants
+        .iter()
+        .map(|plant| {
+            plant
+                .textures
+                .iter()
+                .map(|texture| {
+                    texture_creator
+                        .load_texture(format!("assets/Plants/{texture}"))
+                        .map_err(|e| anyhow!(e))
+                })
+                .collect::<Result<Vec<Texture<'static>>>>()
+        })
+        .collect::<Result<Vec<Vec<Texture<'static>>>>>()?;
+
+    let zombies = zombie_registry
+        .zombies
+        .iter()
+        .map(|zombie| {
+            zombie
+                .textures()
+                .map(|stage| {
+                    let texture = texture_creator
+                        .load_texture(format!("assets/Zombies/{}", stage.texture))
+                        .map_err(|e| anyhow!(e))?;
+                    let frozen = match stage.frozen_texture.as_ref() {
+                        Some(frozen) => texture_creator
+                            .load_texture(format!("assets/Zombies/{frozen}"))
+                            .map_err(|e| anyhow!(e))?,
+                        None => freezed(canvas, texture_creator, &texture)?,
+                    };
+                    Ok((texture, frozen))
+                })
+                .collect::<Result<Vec<(Texture<'static>, Texture<'static>)>>>()
+        })
+        .collect::<Result<Vec<Vec<(Texture<'static>, Texture<'static>)>>>>()?;
 
     Ok(Textures {
         maps,
         sun: texture_creator
             .load_texture("assets/Sun.png")
             .map_err(|e| anyhow!(e))?,
-        plant_sunflower: texture_creator
-            .load_texture("assets/Plants/Sunflower.png")
-            .map_err(|e| anyhow!(e))?,
         pea: texture_creator
             .load_texture("assets/Plants/Pea.png")
             .map_err(|e| anyhow!(e))?,
@@ -106,29 +116,8 @@ pub fn load_textures(
         ice_pea: texture_creator
             .load_texture("assets/Plants/Ice Pea.png")
             .map_err(|e| anyhow!(e))?,
-        plant_simple: texture_creator
-            .load_texture("assets/Plants/Simple.png")
-            .map_err(|e| anyhow!(e))?,
-        plant_fire_simple: texture_creator
-            .load_texture("assets/Plants/Fire Simple.png")
-            .map_err(|e| anyhow!(e))?,
-        plant_ice_simple: texture_creator
-            .load_texture("assets/Plants/Ice Simple.png")
-            .map_err(|e| anyhow!(e))?,
-        plant_triple: texture_creator
-            .load_texture("assets/Plants/Triple.png")
-            .map_err(|e| anyhow!(e))?,
-        plant_nenuphar: texture_creator
-            .load_texture("assets/Plants/Nenuphar.png")
-            .map_err(|e| anyhow!(e))?,
-        zombie_simple,
-        zombie_simple_1,
-        zombie_cone,
-        zombie_cone_1,
-        zombie_freeze_simple,
-        zombie_freeze_simple_1,
-        zombie_freeze_cone,
-        zombie_freeze_cone_1,
+        plants,
+        zombies,
         //font_context,
         font: font_context
             .load_font("assets/OpenSans-Regular.ttf", 128)
@@ -143,47 +132,16 @@ impl Textures {
     pub const fn sun(&'static self) -> &'static Texture<'static> {
         &self.sun
     }
-    pub const fn plant_sunflower(&'static self) -> &'static Texture<'static> {
-        &self.plant_sunflower
-    }
-    pub const fn plant_simple(&'static self) -> &'static Texture<'static> {
-        &self.plant_simple
-    }
-    pub const fn plant_fire_simple(&'static self) -> &'static Texture<'static> {
-        &self.plant_fire_simple
-    }
-    pub const fn plant_ice_simple(&'static self) -> &'static Texture<'static> {
-        &self.plant_ice_simple
-    }
-    pub const fn plant_triple(&'static self) -> &'static Texture<'static> {
-        &self.plant_triple
-    }
-    pub const fn plant_nenuphar(&'static self) -> &'static Texture<'static> {
-        &self.plant_nenuphar
-    }
-    pub const fn zombie_simple(&'static self) -> &'static Texture<'static> {
-        &self.zombie_simple
-    }
-    pub const fn zombie_simple_1(&'static self) -> &'static Texture<'static> {
-        &self.zombie_simple_1
-    }
-    pub const fn zombie_cone(&'static self) -> &'static Texture<'static> {
-        &self.zombie_cone
-    }
-    pub const fn zombie_cone_1(&'static self) -> &'static Texture<'static> {
-        &self.zombie_cone_1
-    }
-    pub const fn zombie_freeze_simple(&'static self) -> &'static Texture<'static> {
-        &self.zombie_freeze_simple
-    }
-    pub const fn zombie_freeze_simple_1(&'static self) -> &'static Texture<'static> {
-        &self.zombie_freeze_simple_1
-    }
-    pub const fn zombie_freeze_cone(&'static self) -> &'static Texture<'static> {
-        &self.zombie_freeze_cone
+    pub fn plant(&'static self, id: u8, texture: usize) -> &'static Texture<'static> {
+        &self.plants[id as usize][texture]
     }
-    pub const fn zombie_freeze_cone_1(&'static self) -> &'static Texture<'static> {
-        &self.zombie_freeze_cone_1
+    pub fn zombie(&'static self, id: u8, stage: usize, frozen: bool) -> &'static Texture<'static> {
+        let (texture, frozen_texture) = &self.zombies[id as usize][stage];
+        if frozen {
+            frozen_texture
+        } else {
+            texture
+        }
     }
     pub const fn pea(&'static self) -> &'static Texture<'static> {
         &self.pea
@@ -1,10 +1,10 @@
-use std::{collections::HashMap, thread, time::Duration};
+use std::{collections::HashMap, path::Path, thread, time::Duration};
 
 use anyhow::{anyhow, Ok, Result};
 use red_sdl::{
     event::Event,
     functions::StateEnum,
-    missing::ui_string::UIString,
+    missing::{rect::scale, ui_string::UIString},
     refs::{MutRef, Ref},
     simple_grid,
     ui_element::{
@@ -24,7 +24,12 @@ use sdl2::{
 };
 use serde_json::Value;
 
-use crate::{default_button, level::Level, texts::load_texts, State};
+use crate::{
+    default_button,
+    level::{parse::LoadError, suspend::SUSPEND_FILE, Level},
+    texts::load_texts,
+    State,
+};
 
 pub struct Win {
     running: bool,
@@ -32,6 +37,7 @@ pub struct Win {
     pub pause: bool,
 
     level: Option<Level>,
+    load_error: Option<UIString>,
 
     main_menu: Grid<Win, State, MainMenuElement>,
     options: Grid<Win, State, UIRect<Win, State>>,
@@ -58,6 +64,7 @@ impl Win {
             surface: FRect::new(0., 0., -1., -1.),
             pause: false,
             level: None,
+            load_error: None,
             main_menu: simple_grid!(
                 ColType::Ratio(380.),
                 ColType::Ratio(275.),
@@ -117,6 +124,20 @@ impl Win {
                                 Color::WHITE,
                             ))
                         })),
+                    Pos { x: 0, y: 8 } => UIRect::new(
+                            Box::new(|_, _, state: Ref<State>| {
+                                if state.suspended {
+                                    StateEnum::Enable
+                                } else {
+                                    StateEnum::Hidden
+                                }
+                            }),
+                            Box::new(|_, _, _| Color::BLACK),
+                        )
+                        .action(Box::new(Self::resume))
+                        .text(Box::new(|_, _self, state| {
+                            Ok((Some(state.as_ref().texts().resume.clone()), Color::WHITE))
+                        })),
                     ).into(),
                 Pos { x: 3, y: 1 } => ScrollView::new(
                         Grid::new(
@@ -131,29 +152,47 @@ impl Win {
                                         x: 0,
                                         y: level as usize * 2,
                                     },
-                                    default_button()
+                                    UIRect::new(
+                                        Box::new(move |_, _, state: Ref<State>| {
+                                            if state.save.is_unlocked(level) {
+                                                StateEnum::Enable
+                                            } else {
+                                                StateEnum::Disable
+                                            }
+                                        }),
+                                        Box::new(move |_, _, state: Ref<State>| {
+                                            if !state.save.is_unlocked(level) {
+                                                Color::RGB(100, 100, 100)
+                                            } else if state.save.progress(level).is_some() {
+                                                Color::RGB(0, 100, 0)
+                                            } else {
+                                                Color::BLACK
+                                            }
+                                        }),
+                                    )
                                     .action(Box::new(
-                                        move |_, mut _self:MutRef<Win>, state, canvas| {
-                                            let mut level = Level::load(
-                                                level
-                                            )?;
-                                            let surface = _self.surface;
-                                            Level::event((&mut level).into(),canvas,
-                                                Event::ElementMove { x: surface.x(), y: surface.y() },
-                                                _self,state)?;
-                                            Level::event((&mut level).into(),canvas,
-                                                Event::ElementResize { width: surface.width(), height: surface.height() },
-                                                _self,state)?;
-
-                                            _self.as_mut().level = Some(level);
-                                            Ok(())
+                                        move |_, mut _self:MutRef<Win>, state: MutRef<State>, canvas| {
+                                            if !state.save.is_unlocked(level) {
+                                                return Ok(());
+                                            }
+                                            Self::open_level(_self, canvas, state, Level::load(level, state.seed, state.zombies(), state.plants()))
                                         },
                                     ))
                                     .text(Box::new(
-                                        move |_, _, _state| {
+                                        move |_, _, _state: Ref<State>| {
                                             UIString::new(
                                                 _state.as_ref().textures().font(),
-                                                format!("{:0>3}", level + 1),
+                                                match _state.save.progress(level) {
+                                                    Some(progress) => format!(
+                                                        "{:0>3}  {}/3  {}:{:0>2}  {}%",
+                                                        level + 1,
+                                                        progress.stars,
+                                                        progress.best_time.as_secs() / 60,
+                                                        progress.best_time.as_secs() % 60,
+                                                        progress.remaining_lawn,
+                                                    ),
+                                                    None => format!("{:0>3}", level + 1),
+                                                },
                                             )
                                             .map(|s| (s, Color::WHITE))
                                         },
@@ -178,7 +217,9 @@ impl Win {
                 RowType::Ratio(40.),
                 RowType::Ratio(20.),
                 RowType::Ratio(40.),
-                RowType::Ratio(300.);
+                RowType::Ratio(20.),
+                RowType::Ratio(40.),
+                RowType::Ratio(240.);
                 Pos { x: 1, y: 1 } => default_button()
                     .action(Box::new(Self::next_lang))
                     .text(Box::new(|_, _self, state| {
@@ -196,7 +237,26 @@ impl Win {
                     .text(Box::new(|_, _self, state| {
                         Ok((Some(state.as_ref().texts()._return.clone()), Color::WHITE))
                     })),
-                Pos { x: 1, y: 7 } => default_button()
+                Pos { x: 1, y: 7 } => UIRect::new(
+                        Box::new(|_, _self: Ref<Win>, _| {
+                            match _self.level.as_ref() {
+                                Some(level)
+                                    if level.started.is_some()
+                                        && level.sim.end.is_none()
+                                        && level.playback.is_none() =>
+                                {
+                                    StateEnum::Enable
+                                }
+                                _ => StateEnum::Hidden,
+                            }
+                        }),
+                        Box::new(|_, _, _| Color::BLACK),
+                    )
+                    .action(Box::new(Self::suspend))
+                    .text(Box::new(|_, _self, state| {
+                        Ok((Some(state.as_ref().texts().save.clone()), Color::WHITE))
+                    })),
+                Pos { x: 1, y: 9 } => default_button()
                     .action(Box::new(Self::quit))
                     .text(Box::new(|_, _self, state| {
                         Ok((Some(state.as_ref().texts().quit.clone()), Color::WHITE))
@@ -259,6 +319,20 @@ impl Win {
             state,
         )?;
 
+        if let Some(replay) = state.as_mut().replay.take() {
+            match Level::load(
+                replay.level,
+                Some(replay.seed),
+                state.zombies(),
+                state.plants(),
+            ) {
+                std::result::Result::Ok(mut level) => {
+                    level.playback = Some(replay);
+                    s.level = Some(level);
+                }
+                Err(e) => s.load_error = Some(Self::load_error(state.as_ref(), e)?),
+            }
+        }
         if let Some(level) = s.level.as_mut() {
             UserControl::event(
                 level.into(),
@@ -291,6 +365,81 @@ impl Win {
         Ok(first.ne("pvz_v0.2.0"))
     }
 
+    fn open_level(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        state: MutRef<State>,
+        level: Result<Level, LoadError>,
+    ) -> Result<()> {
+        let mut level = match level {
+            std::result::Result::Ok(level) => level,
+            Err(e) => {
+                this.load_error = Some(Self::load_error(state.as_ref(), e)?);
+                return Ok(());
+            }
+        };
+        this.load_error = None;
+        let surface = this.surface;
+        Level::event(
+            (&mut level).into(),
+            canvas,
+            Event::ElementMove {
+                x: surface.x(),
+                y: surface.y(),
+            },
+            this,
+            state,
+        )?;
+        Level::event(
+            (&mut level).into(),
+            canvas,
+            Event::ElementResize {
+                width: surface.width(),
+                height: surface.height(),
+            },
+            this,
+            state,
+        )?;
+        this.level = Some(level);
+        Ok(())
+    }
+
+    fn resume(
+        _: MutRef<UIRect<Win, State>>,
+        mut this: MutRef<Self>,
+        mut state: MutRef<State>,
+        canvas: &Canvas<Window>,
+    ) -> Result<()> {
+        let level = Level::resume(state.zombies(), state.plants());
+        state.suspended = Path::new(SUSPEND_FILE).exists();
+        Self::open_level(this, canvas, state, level)?;
+        if let Some(level) = this.as_mut().level.as_mut() {
+            Level::start(level.into(), canvas, state)?;
+        }
+        Ok(())
+    }
+
+    fn suspend(
+        _: MutRef<UIRect<Win, State>>,
+        mut this: MutRef<Self>,
+        mut state: MutRef<State>,
+        _: &Canvas<Window>,
+    ) -> Result<()> {
+        if let Some(level) = this.level.as_mut() {
+            level.save_record()?;
+            level.suspend()?;
+            state.suspended = true;
+        }
+        this.level = None;
+        this.pause = false;
+        Ok(())
+    }
+
+    fn load_error(state: &'static State, error: LoadError) -> Result<UIString> {
+        Ok(UIString::new(state.textures().font(), error.to_string())?
+            .unwrap_or_else(|| state.texts().invalid_level.clone()))
+    }
+
     fn next_lang(
         _: MutRef<UIRect<Win, State>>,
         _: MutRef<Self>,
@@ -323,6 +472,9 @@ impl Win {
         _: MutRef<State>,
         _: &Canvas<Window>,
     ) -> Result<()> {
+        if let Some(level) = this.level.as_mut() {
+            level.save_record()?;
+        }
         this.level = None;
         this.pause = false;
         Ok(())
@@ -334,7 +486,14 @@ impl Win {
         _: MutRef<State>,
         _: &Canvas<Window>,
     ) -> Result<()> {
-        this.running = false;
+        this.close()
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if let Some(level) = self.level.as_mut() {
+            level.save_record()?;
+        }
+        self.running = false;
         Ok(())
     }
 
@@ -356,7 +515,9 @@ impl Win {
         canvas: &Canvas<Window>,
     ) -> Result<()> {
         if let Some(level) = this.as_mut().level.as_mut() {
-            Level::start(level.into(), canvas, state)?;
+            if level.playback.is_none() {
+                Level::start(level.into(), canvas, state)?;
+            }
         }
         Ok(())
     }
@@ -426,7 +587,7 @@ impl UserControl<(), State> for Win {
                 }
                 return Ok(());
             }
-            Event::Quit { .. } => this.running = false,
+            Event::Quit { .. } => this.close()?,
             Event::KeyDown {
                 keycode: Some(Keycode::Escape),
                 ..
@@ -503,6 +664,15 @@ impl UserControl<(), State> for Win {
         canvas.set_draw_color(Color::RGB(50, 50, 50));
         canvas.clear();
 
-        UserControl::draw((&this.main_menu).into(), canvas, this, state)
+        UserControl::draw((&this.main_menu).into(), canvas, this, state)?;
+        if let Some(error) = this.load_error.as_ref() {
+            error.draw(
+                canvas,
+                None,
+                scale(this.surface, FRect::new(0.05, 0.8, 0.9, 0.05)),
+                Color::RED,
+            )?;
+        }
+        Ok(())
     }
 }
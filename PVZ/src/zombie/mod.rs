@@ -0,0 +1,297 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use sdl2::{rect::FRect, render::Texture};
+use serde_json::{json, Value};
+
+use crate::{
+    level::parse::{duration_value, f32_value, Field, LoadError},
+    projectile::DamageType,
+    textures::Textures,
+};
+use registry::{ZombieDefinition, ZombieRegistry};
+
+pub mod registry;
+
+pub trait Zombie {
+    fn texture(&self, textures: &'static Textures) -> &'static Texture<'static>;
+    fn rect(&self, y: f32) -> FRect;
+    fn update(&mut self, elapsed: Duration) -> Result<()>;
+
+    fn set_x(&mut self, x: f32);
+    fn uid(&self) -> u64;
+    fn set_uid(&mut self, uid: u64);
+    fn vault(&mut self) -> bool;
+    fn airborne(&self) -> bool;
+    fn submerged(&self) -> bool;
+    fn set_eating(&mut self, eating: bool);
+    fn hit(&mut self, hit: Hit) -> (bool, bool);
+    fn hit_box(&self, y: f32) -> FRect;
+    fn freezed(&self) -> bool;
+    fn eat_rate(&self) -> f32;
+    fn save(&self) -> Value;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HitSource {
+    Front,
+    Back,
+    Side,
+    Above,
+    Area,
+}
+
+#[derive(Clone, Copy)]
+pub struct Hit {
+    pub damage_amount: usize,
+    pub damage_type: DamageType,
+    pub source: HitSource,
+    pub propagated: bool,
+}
+
+impl Hit {
+    pub const fn new(damage_amount: usize, damage_type: DamageType, source: HitSource) -> Self {
+        Self {
+            damage_amount,
+            damage_type,
+            source,
+            propagated: false,
+        }
+    }
+
+    pub const fn propagated(self) -> Self {
+        Self {
+            source: HitSource::Area,
+            propagated: true,
+            ..self
+        }
+    }
+}
+
+pub fn reachable(zombie: &dyn Zombie, reaches_air: bool) -> bool {
+    !zombie.submerged() && (!zombie.airborne() || reaches_air)
+}
+
+pub fn zombie_from_json(
+    json: &Field,
+    registry: &'static ZombieRegistry,
+) -> Result<Box<dyn Zombie>, LoadError> {
+    let zombie_type = json.get("type");
+    let definition = registry
+        .id(zombie_type.str()?)
+        .and_then(|id| registry.get(id))
+        .ok_or_else(|| zombie_type.invalid("a zombie type"))?;
+    let health = json.get("health");
+    let armor = json.get("armor");
+    let armor_health = armor
+        .items()?
+        .iter()
+        .map(|health| health.u64().map(|health| health as usize))
+        .collect::<Result<Vec<usize>, LoadError>>()?;
+    if armor_health.len() != definition.armor.len()
+        || armor_health
+            .iter()
+            .zip(definition.armor.iter())
+            .any(|(&health, piece)| health > piece.max_health())
+    {
+        return Err(armor.invalid("a health for each armor piece"));
+    }
+    Ok(Box::new(ZombieBase {
+        definition,
+        uid: json.get("uid").u64()?,
+        x: json.get("x").f32()?,
+        health: match health.u64()? as usize {
+            health if (1..=definition.max_health()).contains(&health) => health,
+            _ => return Err(health.invalid("a health inside the zombie stages")),
+        },
+        armor: armor_health,
+        freeze: json.get("freeze_ns").duration()?,
+        vaulted: json.get("vaulted").bool()?,
+        eating: false,
+    }))
+}
+
+impl ZombieBase {
+    fn speed(&self) -> f32 {
+        let speed = match &self.definition.vault {
+            Some(vault) if !self.vaulted => vault.speed,
+            _ => self.definition.speed,
+        };
+        self.definition
+            .armor
+            .iter()
+            .zip(self.armor.iter())
+            .filter(|(_, &health)| health == 0)
+            .filter_map(|(piece, _)| piece.rage)
+            .fold(speed, |speed, rage| speed * rage)
+    }
+}
+
+pub struct ZombieBase {
+    definition: &'static ZombieDefinition,
+    uid: u64,
+    x: f32,
+    health: usize,
+    armor: Vec<usize>,
+    freeze: Duration,
+    vaulted: bool,
+    eating: bool,
+}
+
+impl Zombie for ZombieBase {
+    fn texture(&self, textures: &'static Textures) -> &'static Texture<'static> {
+        textures.zombie(
+            self.definition.id,
+            self.definition
+                .appearance(self.health, &self.armor, self.vaulted, self.submerged())
+                .0,
+            !self.freeze.is_zero(),
+        )
+    }
+
+    fn rect(&self, y: f32) -> FRect {
+        FRect::new(
+            self.x,
+            y,
+            self.definition.width,
+            self.definition
+                .appearance(self.health, &self.armor, self.vaulted, self.submerged())
+                .1
+                .height,
+        )
+    }
+
+    fn update(&mut self, elapsed: Duration) -> Result<()> {
+        let mut speed = self.speed();
+        if !self.freeze.is_zero() {
+            if self.freeze > elapsed {
+                self.freeze -= elapsed
+            } else {
+                self.freeze = Duration::ZERO;
+            }
+            speed *= self.definition.frozen_speed;
+        }
+        self.x -= elapsed.as_secs_f32() * speed;
+        Ok(())
+    }
+
+    fn set_x(&mut self, x: f32) {
+        self.x = x;
+    }
+
+    fn uid(&self) -> u64 {
+        self.uid
+    }
+
+    fn set_uid(&mut self, uid: u64) {
+        self.uid = uid;
+    }
+
+    fn vault(&mut self) -> bool {
+        if self.definition.vault.is_none() || self.vaulted {
+            return false;
+        }
+        self.vaulted = true;
+        true
+    }
+
+    fn airborne(&self) -> bool {
+        self.definition
+            .armor
+            .iter()
+            .zip(self.armor.iter())
+            .any(|(piece, &health)| piece.flying && health > 0)
+    }
+
+    fn submerged(&self) -> bool {
+        self.definition.submerged.is_some() && !self.eating
+    }
+
+    fn set_eating(&mut self, eating: bool) {
+        self.eating = eating;
+    }
+
+    fn hit_box(&self, y: f32) -> FRect {
+        FRect::new(
+            self.x + self.definition.hitbox_x,
+            y,
+            self.definition.hitbox_width,
+            self.definition
+                .appearance(self.health, &self.armor, self.vaulted, self.submerged())
+                .1
+                .height,
+        )
+    }
+
+    fn eat_rate(&self) -> f32 {
+        if self.freeze.is_zero() {
+            self.definition.eat_rate
+        } else {
+            self.definition.eat_rate * self.definition.frozen_speed
+        }
+    }
+
+    fn hit(&mut self, hit: Hit) -> (bool, bool) {
+        let mut propagate = false;
+        match hit.damage_type {
+            DamageType::Normal => {}
+            DamageType::Fire => {
+                self.freeze = Duration::ZERO;
+                propagate = !hit.propagated;
+            }
+            DamageType::Ice => {
+                let shielded = self
+                    .definition
+                    .armor
+                    .iter()
+                    .zip(self.armor.iter())
+                    .find(|(piece, &health)| piece.guards(health, hit.source))
+                    .is_some_and(|(piece, _)| piece.blocks_freeze);
+                if !shielded {
+                    self.freeze = Duration::new(10, 0);
+                }
+                propagate = !hit.propagated;
+                if !propagate {
+                    return (false, propagate);
+                }
+            }
+        }
+        let mut damage_amount = hit.damage_amount;
+        for (piece, health) in self.definition.armor.iter().zip(self.armor.iter_mut()) {
+            if !piece.guards(*health, hit.source) {
+                continue;
+            }
+            if *health > damage_amount {
+                *health -= damage_amount;
+                return (false, propagate);
+            }
+            damage_amount = if piece.overflow {
+                damage_amount - *health
+            } else {
+                0
+            };
+            *health = 0;
+        }
+        if self.health <= damage_amount {
+            return (true, propagate);
+        }
+        self.health -= damage_amount;
+        (false, propagate)
+    }
+
+    fn freezed(&self) -> bool {
+        !self.freeze.is_zero()
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "type": self.definition.name,
+            "uid": self.uid,
+            "x": f32_value(self.x),
+            "health": self.health,
+            "armor": self.armor,
+            "freeze_ns": duration_value(self.freeze),
+            "vaulted": self.vaulted,
+        })
+    }
+}
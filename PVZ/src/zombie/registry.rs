@@ -0,0 +1,291 @@
+use std::time::Duration;
+
+use crate::level::{
+    config::RowType,
+    parse::{read_json, Field, LoadError},
+};
+
+use super::{HitSource, Zombie, ZombieBase};
+
+pub struct ZombieStage {
+    pub health: usize,
+    pub height: f32,
+    pub texture: String,
+    pub frozen_texture: Option<String>,
+}
+
+impl ZombieStage {
+    fn load_all(json: &Field) -> Result<Vec<Self>, LoadError> {
+        let stages = json
+            .items()?
+            .iter()
+            .map(|stage| {
+                Ok(ZombieStage {
+                    health: stage.get("health").u64()? as usize,
+                    height: stage.get("height").f32()? / 720.,
+                    texture: stage.get("texture").str()?.to_owned(),
+                    frozen_texture: stage
+                        .get("frozen_texture")
+                        .optional()
+                        .map(|t| t.str().map(str::to_owned))
+                        .transpose()?,
+                })
+            })
+            .collect::<Result<Vec<ZombieStage>, LoadError>>()?;
+        if stages.is_empty()
+            || stages[0].health == 0
+            || stages.windows(2).any(|s| s[0].health >= s[1].health)
+        {
+            return Err(json.invalid("stages with increasing health"));
+        }
+        Ok(stages)
+    }
+
+    fn max_health(stages: &[Self]) -> usize {
+        stages.last().map_or(0, |stage| stage.health)
+    }
+
+    fn index(stages: &[Self], health: usize) -> usize {
+        stages
+            .iter()
+            .position(|stage| health <= stage.health)
+            .unwrap_or(stages.len() - 1)
+    }
+}
+
+pub struct ZombieArmor {
+    pub name: String,
+    pub stages: Vec<ZombieStage>,
+    pub blocks_freeze: bool,
+    pub overflow: bool,
+    pub shield: bool,
+    pub flying: bool,
+    pub rage: Option<f32>,
+}
+
+impl ZombieArmor {
+    fn load(json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            name: json.get("name").str()?.to_owned(),
+            stages: ZombieStage::load_all(&json.get("stages"))?,
+            blocks_freeze: json
+                .get("blocks_freeze")
+                .optional()
+                .map(Field::bool)
+                .transpose()?
+                .unwrap_or(false),
+            overflow: json
+                .get("overflow")
+                .optional()
+                .map(Field::bool)
+                .transpose()?
+                .unwrap_or(true),
+            shield: json
+                .get("shield")
+                .optional()
+                .map(Field::bool)
+                .transpose()?
+                .unwrap_or(false),
+            flying: json
+                .get("flying")
+                .optional()
+                .map(Field::bool)
+                .transpose()?
+                .unwrap_or(false),
+            rage: json
+                .get("rage_speed")
+                .optional()
+                .map(Field::f32)
+                .transpose()?,
+        })
+    }
+
+    pub fn max_health(&self) -> usize {
+        ZombieStage::max_health(&self.stages)
+    }
+
+    pub fn guards(&self, health: usize, source: HitSource) -> bool {
+        health > 0 && (!self.shield || source == HitSource::Front)
+    }
+}
+
+pub struct ZombieVault {
+    pub speed: f32,
+    pub stages: Vec<ZombieStage>,
+}
+
+impl ZombieVault {
+    fn load(json: &Field) -> Result<Self, LoadError> {
+        Ok(Self {
+            speed: json.get("speed").f32()? / 1280.,
+            stages: ZombieStage::load_all(&json.get("stages"))?,
+        })
+    }
+}
+
+pub struct ZombieDefinition {
+    pub id: u8,
+    pub name: String,
+    pub speed: f32,
+    pub frozen_speed: f32,
+    pub eat_rate: f32,
+    pub width: f32,
+    pub hitbox_x: f32,
+    pub hitbox_width: f32,
+    pub stages: Vec<ZombieStage>,
+    pub armor: Vec<ZombieArmor>,
+    pub vault: Option<ZombieVault>,
+    pub submerged: Option<Vec<ZombieStage>>,
+    pub rows: Vec<RowType>,
+}
+
+impl ZombieDefinition {
+    fn load(id: u8, json: &Field) -> Result<Self, LoadError> {
+        let stages = ZombieStage::load_all(&json.get("stages"))?;
+        let armor = match json.get("armor").optional() {
+            Some(armor) => armor
+                .items()?
+                .iter()
+                .map(ZombieArmor::load)
+                .collect::<Result<Vec<ZombieArmor>, LoadError>>()?,
+            None => Vec::new(),
+        };
+        let hitbox = json.get("hitbox");
+        Ok(Self {
+            id,
+            name: json.get("name").str()?.to_owned(),
+            speed: json.get("speed").f32()? / 1280.,
+            frozen_speed: json
+                .get("frozen_speed")
+                .optional()
+                .map(Field::f32)
+                .transpose()?
+                .unwrap_or(0.5),
+            eat_rate: json.get("eat_rate").f32()?,
+            width: json.get("width").f32()? / 1280.,
+            hitbox_x: hitbox.get("x").f32()? / 1280.,
+            hitbox_width: hitbox.get("width").f32()? / 1280.,
+            stages,
+            armor,
+            vault: json
+                .get("vault")
+                .optional()
+                .map(ZombieVault::load)
+                .transpose()?,
+            submerged: json
+                .get("submerged")
+                .optional()
+                .map(ZombieStage::load_all)
+                .transpose()?,
+            rows: match json.get("rows").optional() {
+                Some(rows) => rows
+                    .items()?
+                    .iter()
+                    .map(RowType::load)
+                    .collect::<Result<Vec<RowType>, LoadError>>()?,
+                None => vec![RowType::Grass],
+            },
+        })
+    }
+
+    pub fn max_health(&self) -> usize {
+        ZombieStage::max_health(&self.stages)
+    }
+
+    pub fn textures(&self) -> impl Iterator<Item = &ZombieStage> {
+        self.stages
+            .iter()
+            .chain(self.armor.iter().flat_map(|armor| armor.stages.iter()))
+            .chain(self.vault.iter().flat_map(|vault| vault.stages.iter()))
+            .chain(self.submerged.iter().flatten())
+    }
+
+    pub fn appearance(
+        &self,
+        health: usize,
+        armor: &[usize],
+        vaulted: bool,
+        submerged: bool,
+    ) -> (usize, &ZombieStage) {
+        let mut offset = self.stages.len();
+        for (piece, &remaining) in self.armor.iter().zip(armor) {
+            if remaining > 0 {
+                let stage = ZombieStage::index(&piece.stages, remaining);
+                return (offset + stage, &piece.stages[stage]);
+            }
+            offset += piece.stages.len();
+        }
+        if let Some(vault) = &self.vault {
+            if !vaulted {
+                let stage = ZombieStage::index(&vault.stages, health);
+                return (offset + stage, &vault.stages[stage]);
+            }
+            offset += vault.stages.len();
+        }
+        if let Some(stages) = self.submerged.as_ref().filter(|_| submerged) {
+            let stage = ZombieStage::index(stages, health);
+            return (offset + stage, &stages[stage]);
+        }
+        let stage = ZombieStage::index(&self.stages, health);
+        (stage, &self.stages[stage])
+    }
+
+    pub fn spawn(&'static self) -> Box<dyn Zombie> {
+        Box::new(ZombieBase {
+            definition: self,
+            uid: 0,
+            x: 1.,
+            health: self.max_health(),
+            armor: self.armor.iter().map(ZombieArmor::max_health).collect(),
+            freeze: Duration::new(0, 0),
+            vaulted: false,
+            eating: false,
+        })
+    }
+}
+
+pub struct ZombieRegistry {
+    pub zombies: Vec<ZombieDefinition>,
+}
+
+impl ZombieRegistry {
+    pub fn load() -> Result<Self, LoadError> {
+        let file = "assets/zombies.json";
+        let json = read_json(file)?;
+        let json = Field::root(file, &json);
+        let version = json.get("version");
+        if version.u64()? != 1 {
+            return Err(version.invalid("format version 1"));
+        }
+        let zombies = json.get("zombies").items()?;
+        if zombies.len() > u8::MAX as usize {
+            return Err(json.get("zombies").invalid("at most 255 zombies"));
+        }
+        Ok(Self {
+            zombies: zombies
+                .iter()
+                .enumerate()
+                .map(|(id, zombie)| ZombieDefinition::load(id as u8, zombie))
+                .collect::<Result<Vec<ZombieDefinition>, LoadError>>()?,
+        })
+    }
+
+    pub fn get(&self, id: u8) -> Option<&ZombieDefinition> {
+        self.zombies.get(id as usize)
+    }
+
+    pub fn id(&self, name: &str) -> Option<u8> {
+        self.zombies
+            .iter()
+            .find(|zombie| zombie.name == name)
+            .map(|zombie| zombie.id)
+    }
+
+    pub fn name(&self, id: u8) -> Option<&str> {
+        self.get(id).map(|zombie| zombie.name.as_str())
+    }
+
+    pub fn spawn(&'static self, id: u8) -> Box<dyn Zombie> {
+        self.zombies[id as usize].spawn()
+    }
+}
@@ -3,8 +3,7 @@ use std::{collections::HashMap, ops::Range, time::Duration};
 use anyhow::{anyhow, Error, Result};
 use pvz::{
     default_button,
-    level::{config::Map, Level},
-    zombie::{valide_zombie_id, zombie_from_id},
+    level::{config::Map, simulation::SpawnZombie, Level},
 };
 use red_sdl::{
     event::Event,
@@ -65,14 +64,14 @@ impl LevelConfig {
         canvas: &Canvas<Window>,
     ) -> Result<Self> {
         let font = state.as_ref().textures().font();
-        let level = Level::load(id)?;
-        let map_id = level.map.id;
-        let money = level.money;
+        let level = Level::load(id, None, state.as_ref().zombies(), state.as_ref().plants())?;
+        let map_id = level.sim.map.id;
+        let money = level.sim.money;
         let mut elements = HashMap::new();
         let mut index_element = 0;
-        for (time, zombies) in level.spawn_waits.iter().enumerate().map(|(i, wait)| {
+        for (time, zombies) in level.sim.spawn_waits.iter().enumerate().map(|(i, wait)| {
             let mut zombies: HashMap<u8, u32> = HashMap::new();
-            for z in level.spawn_zombies[i].iter().map(|z| z.0) {
+            for z in level.sim.spawn_zombies[i].iter().map(|z| z.id) {
                 if let Some(v) = zombies.get_mut(&z) {
                     *v += 1;
                 } else {
@@ -83,9 +82,14 @@ impl LevelConfig {
             zombies.sort_by(|z1, z2| (z1.0 - z2.0).cmp(&z1.0));
             (wait, zombies)
         }) {
-            zombie_time(font, &mut elements, index_element, time.as_secs())?;
+            zombie_time(font, &mut elements, index_element, time.as_millis() as u64)?;
             for (_type, amount) in zombies.into_iter() {
-                zombie_row(font, &mut elements, index_element, _type, amount)?;
+                let name = state
+                    .as_ref()
+                    .zombies()
+                    .name(_type)
+                    .ok_or(anyhow!("zombie id not found"))?;
+                zombie_row(font, &mut elements, index_element, name, amount)?;
                 index_element += 1;
             }
             wave_buttons(font, &mut elements, index_element);
@@ -306,29 +310,30 @@ impl LevelConfig {
     }
 
     pub fn try_save(&mut self) -> Result<()> {
-        self.level.money = self
+        self.level.sim.money = self
             .get_money_text()
             .parse::<u32>()
             .map_err(|e| anyhow!(e))?;
-        self.level.map = Map::load(self.get_map_text().parse::<u8>().map_err(|e| anyhow!(e))?)
+        self.level.sim.map = Map::load(self.get_map_text().parse::<u8>().map_err(|e| anyhow!(e))?)
             .map_err(|e| anyhow!(e))?;
 
-        self.level.spawn_waits = self
+        self.level.sim.spawn_waits = self
             .waves_indexes()
             .map(|i| {
                 if let Some(LevelSubElement::TextBox(t)) = self.get_level_config().get_element(0, i)
                 {
-                    Ok(Duration::from_secs(
-                        t.text().as_str().parse::<u8>().map_err(|e| anyhow!(e))? as u64,
+                    Ok(Duration::from_millis(
+                        t.text().as_str().parse::<u64>().map_err(|e| anyhow!(e))?,
                     ))
                 } else {
                     Err(anyhow!("wrongly placed wave time"))
                 }
             })
             .collect::<Result<Vec<Duration>, Error>>()?;
-        self.level.spawn_zombies = self
+        self.level.sim.spawn_zombies = self
             .waves()
-            .map(|i| {
+            .enumerate()
+            .map(|(wave, i)| {
                 Ok(i.map(|zi| {
                     let Some(LevelSubElement::TextBox(zombie)) =
                         self.get_level_config().get_element(2, zi)
@@ -341,14 +346,12 @@ impl LevelConfig {
                         return Err(anyhow!("wrongly placed zombie amount"));
                     };
 
-                    let zombie = zombie
-                        .text()
-                        .as_str()
-                        .parse::<u8>()
-                        .map_err(|e| anyhow!(e))?;
-                    if !valide_zombie_id(zombie) {
-                        return Err(anyhow!("invalide zombie id"));
-                    }
+                    let zombie = self
+                        .level
+                        .sim
+                        .zombie_registry
+                        .id(zombie.text().as_str())
+                        .ok_or(anyhow!("invalide zombie type"))?;
                     let amount = amount
                         .text()
                         .as_str()
@@ -357,16 +360,32 @@ impl LevelConfig {
                     if amount == 0 {
                         return Err(anyhow!("Amount too low"));
                     }
-                    Ok::<Vec<(u8, f32, f32)>, Error>(
-                        (0..amount).map(move |_| (zombie, 0., 0.)).collect(),
+                    let mut lanes = self
+                        .level
+                        .sim
+                        .spawn_zombies
+                        .get(wave)
+                        .into_iter()
+                        .flatten()
+                        .filter(|z| z.id == zombie)
+                        .map(|z| z.lane);
+                    Ok::<Vec<SpawnZombie>, Error>(
+                        (0..amount)
+                            .map(|_| SpawnZombie {
+                                id: zombie,
+                                lane: lanes.next().flatten(),
+                                x: 0.,
+                                y: 0.,
+                            })
+                            .collect(),
                     )
                 })
-                .collect::<Result<Vec<Vec<(u8, f32, f32)>>, Error>>()?
+                .collect::<Result<Vec<Vec<SpawnZombie>>, Error>>()?
                 .into_iter()
                 .flatten()
                 .collect())
             })
-            .collect::<Result<Vec<Vec<(u8, f32, f32)>>, Error>>()?;
+            .collect::<Result<Vec<Vec<SpawnZombie>>, Error>>()?;
         Ok(())
     }
 
@@ -555,7 +574,7 @@ fn zombie_time(
     font: &'static Font<'_, '_>,
     elements: &mut HashMap<Pos, LevelSubElement>,
     index_element: usize,
-    time_secs: u64,
+    time_ms: u64,
 ) -> Result<(), Error> {
     elements.insert(
         Pos {
@@ -564,13 +583,13 @@ fn zombie_time(
         },
         TextBox::new(
             font,
-            UIString::new(font, time_secs.to_string())?.ok_or(anyhow!("sized"))?,
+            UIString::new(font, time_ms.to_string())?.ok_or(anyhow!("sized"))?,
             Box::new(|_, _, _| StateEnum::Enable),
             Box::new(|_, _, _| Color::RGBA(255, 255, 255, 100)),
             Box::new(|_, _, _| Color::WHITE),
             Box::new(|_, _, _| Color::WHITE),
             Box::new(|t, _, _| {
-                if t.text().as_str().parse::<u8>().is_ok() {
+                if t.text().as_str().parse::<u64>().is_ok() {
                     Color::BLACK
                 } else {
                     Color::RED
@@ -586,7 +605,7 @@ fn zombie_row(
     font: &'static Font<'_, '_>,
     elements: &mut HashMap<Pos, LevelSubElement>,
     index_element: usize,
-    _type: u8,
+    _type: &str,
     amount: u32,
 ) -> Result<(), Error> {
     elements.insert(
@@ -620,22 +639,21 @@ fn zombie_row(
 
 fn edit_zombie_id(
     font: &'static Font<'static, 'static>,
-    _type: u8,
+    _type: &str,
 ) -> Result<LevelSubElement, Error> {
     Ok(TextBox::new(
         font,
-        UIString::new(font, _type.to_string())?.ok_or(anyhow!("sized"))?,
+        UIString::new(font, _type.to_owned())?.ok_or(anyhow!("sized"))?,
         Box::new(|_, _, _| StateEnum::Enable),
         Box::new(|_, _, _| Color::RGBA(255, 255, 255, 100)),
         Box::new(|_, _, _| Color::WHITE),
         Box::new(|_, _, _| Color::WHITE),
-        Box::new(|t, _, _| {
-            if let Ok(id) = t.text().as_str().parse::<u8>() {
-                if valide_zombie_id(id) {
-                    return Color::BLACK;
-                }
+        Box::new(|t, _, state| {
+            if state.zombies().id(t.text().as_str()).is_some() {
+                Color::BLACK
+            } else {
+                Color::RED
             }
-            Color::RED
         }),
     )
     .into())
@@ -721,7 +739,13 @@ fn add_wave_action(
                 *child_size += SCROLL_ELEMENT_SIZE * 3.;
                 let font = state.as_ref().textures().font();
                 zombie_time(font, &mut elements, index_element, 0)?;
-                zombie_row(font, &mut elements, index_element, 255, 1)?;
+                zombie_row(
+                    font,
+                    &mut elements,
+                    index_element,
+                    default_zombie(state.as_ref()),
+                    1,
+                )?;
                 wave_buttons(font, &mut elements, index_element + 1);
                 Ok(())
             }));
@@ -803,7 +827,7 @@ fn add_zombie_action(
                     state.as_ref().textures().font(),
                     &mut elements,
                     index_element,
-                    255,
+                    default_zombie(state.as_ref()),
                     1,
                 )?;
                 let f = rows.first().map_or(1., |a| a.to_px(1.));
@@ -908,11 +932,13 @@ fn zombie_image_image(
         if let Some(LevelSubElement::TextBox(t)) =
             _self.get_level_config().get_element(2, index_element)
         {
-            match t.text().as_str().parse::<u8>() {
-                Ok(id) if valide_zombie_id(id) => {
-                    Ok(zombie_from_id(id).texture(state.as_ref().textures()))
-                }
-                _ => Err(anyhow!("error zombie id image should be hidden")),
+            match state.as_ref().zombies().id(t.text().as_str()) {
+                Some(id) => Ok(state
+                    .as_ref()
+                    .zombies()
+                    .spawn(id)
+                    .texture(state.as_ref().textures())),
+                None => Err(anyhow!("error zombie id image should be hidden")),
             }
         } else {
             Err(anyhow!("error zombie id image should be hidden"))
@@ -923,16 +949,24 @@ fn zombie_image_image(
 fn zombie_image_state(
     index_element: usize,
 ) -> impl Fn(Ref<UIRect<LevelConfig, State>>, Ref<LevelConfig>, Ref<State>) -> StateEnum {
-    move |_, _self, _| {
+    move |_, _self, state| {
         if let Some(LevelSubElement::TextBox(t)) =
             _self.get_level_config().get_element(2, index_element)
         {
-            match t.text().as_str().parse::<u8>() {
-                Ok(id) if valide_zombie_id(id) => StateEnum::Enable,
-                _ => StateEnum::Hidden,
+            match state.zombies().id(t.text().as_str()) {
+                Some(_) => StateEnum::Enable,
+                None => StateEnum::Hidden,
             }
         } else {
             StateEnum::Hidden
         }
     }
 }
+
+fn default_zombie(state: &'static State) -> &'static str {
+    state
+        .zombies()
+        .zombies
+        .first()
+        .map_or("", |zombie| zombie.name.as_str())
+}
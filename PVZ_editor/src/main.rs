@@ -4,13 +4,15 @@ mod pin;
 mod rows_editor;
 mod win;
 
-use std::fs;
+use std::{collections::BTreeSet, fs};
 
 use anyhow::{anyhow, Result};
 use pvz::{
+    plants::registry::PlantRegistry,
     save::SaveFile,
     texts::{Lang, Texts},
     textures::{load_textures, Textures},
+    zombie::registry::ZombieRegistry,
 };
 use red_sdl::{missing::ui_string::UIString, refs::MutRef, run_event, ui_element::ui_rect::UIRect};
 use sdl2::{render::Canvas, video::Window};
@@ -20,6 +22,8 @@ use crate::win::Win;
 
 pub struct State {
     textures: Textures,
+    zombies: &'static ZombieRegistry,
+    plants: &'static PlantRegistry,
     save: SaveFile,
     page_a: Page,
     page_b: Page,
@@ -38,25 +42,20 @@ pub fn main() -> Result<()> {
         720,
         |window| window.fullscreen_desktop().resizable(),
         |canvas| {
-            let textures = load_textures(canvas, Box::leak(Box::new(canvas.texture_creator())))?;
-            let maps_count = fs::read_dir("assets/maps")
-                .map_err(|e| anyhow!(e))?
-                .filter(|f| {
-                    if let Ok(d) = f {
-                        d.file_name()
-                            .to_str()
-                            .is_some_and(|s| s.to_lowercase().ends_with("data"))
-                    } else {
-                        false
-                    }
-                })
-                .count();
-            if maps_count == 0 || fs::read_dir("assets/maps").map_err(|e| anyhow!(e))?.count() > 99
-            {
+            let zombies: &'static ZombieRegistry = Box::leak(Box::new(ZombieRegistry::load()?));
+            let plants: &'static PlantRegistry = Box::leak(Box::new(PlantRegistry::load()?));
+            let textures = load_textures(
+                canvas,
+                Box::leak(Box::new(canvas.texture_creator())),
+                zombies,
+                plants,
+            )?;
+            let maps_count = count_ids("assets/maps")?;
+            if maps_count == 0 || maps_count > 99 {
                 return Err(anyhow!("Too much or no levels"));
             }
-            let levels_count = fs::read_dir("levels").map_err(|e| anyhow!(e))?.count();
-            if levels_count == 0 || fs::read_dir("levels").map_err(|e| anyhow!(e))?.count() > 99 {
+            let levels_count = count_ids("levels")?;
+            if levels_count == 0 || levels_count > 99 {
                 return Err(anyhow!("Too much or no levels"));
             }
             Ok(State::new(
@@ -64,14 +63,38 @@ pub fn main() -> Result<()> {
                 levels_count as u8,
                 SaveFile::load()?,
                 textures,
+                zombies,
+                plants,
             ))
         },
         Win::new,
     )
 }
 
+fn count_ids(dir: &str) -> Result<usize> {
+    Ok(fs::read_dir(dir)
+        .map_err(|e| anyhow!(e))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let extension = path.extension()?.to_str()?.to_lowercase();
+            if extension != "data" && extension != "json" {
+                return None;
+            }
+            path.file_stem()?.to_str()?.parse::<u8>().ok()
+        })
+        .collect::<BTreeSet<u8>>()
+        .len())
+}
+
 impl State {
-    pub fn new(maps_count: u8, levels_count: u8, save: SaveFile, textures: Textures) -> Self {
+    pub fn new(
+        maps_count: u8,
+        levels_count: u8,
+        save: SaveFile,
+        textures: Textures,
+        zombies: &'static ZombieRegistry,
+        plants: &'static PlantRegistry,
+    ) -> Self {
         Self {
             maps_count,
             levels_count,
@@ -80,6 +103,8 @@ impl State {
             page: false,
             save,
             textures,
+            zombies,
+            plants,
             en: Texts::default(),
             fr: Texts::default(),
         }
@@ -124,6 +149,14 @@ impl State {
         &self.textures
     }
 
+    pub const fn zombies(&self) -> &'static ZombieRegistry {
+        self.zombies
+    }
+
+    pub const fn plants(&self) -> &'static PlantRegistry {
+        self.plants
+    }
+
     pub const fn texts(&self) -> &Texts {
         match self.save.langage {
             Lang::EN => &self.en,
@@ -146,7 +179,10 @@ pub fn load_texts(mut state: MutRef<State>) {
         update_available: UIString::new_const(font, "An update is available."),
         up_to_date: UIString::new_const(font, "You are up to date."),
         loading: UIString::new_const(font, "Loading..."),
+        invalid_level: UIString::new_const(font, "This level file is invalid."),
         save: UIString::new_const(font, "Save"),
+        resume: UIString::new_const(font, "Resume"),
+        shovel: UIString::new_const(font, "Shovel"),
     };
     state.as_mut().fr = Texts {
         lang: UIString::new_const(font, "Français"),
@@ -160,6 +196,9 @@ pub fn load_texts(mut state: MutRef<State>) {
         update_available: UIString::new_const(font, "Une mise à jour est disponible."),
         up_to_date: UIString::new_const(font, "Vous êtes à jour."),
         loading: UIString::new_const(font, "Chargement..."),
+        invalid_level: UIString::new_const(font, "Ce fichier de niveau est invalide."),
         save: UIString::new_const(font, "Sauvegarder"),
+        resume: UIString::new_const(font, "Reprendre"),
+        shovel: UIString::new_const(font, "Pelle"),
     };
 }
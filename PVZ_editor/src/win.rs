@@ -1,7 +1,7 @@
-use std::{collections::HashMap, fs, time::Duration};
+use std::{collections::HashMap, time::Duration};
 
 use anyhow::{anyhow, Result};
-use pvz::default_button;
+use pvz::{default_button, level::config::LevelFile};
 use red_sdl::{
     event::Event,
     missing::ui_string::UIString,
@@ -119,7 +119,7 @@ impl Page {
                     move |_, _self: MutRef<Win>, mut state: MutRef<State>, canvas| {
                         let level = state.as_ref().levels_count;
                         state.as_mut().levels_count += 1;
-                        fs::write(format!("levels/{level}.data"), [0;6]).map_err(|e| anyhow!(e))?;
+                        LevelFile { map: 0, money: 0, plants: state.plants().ids(), spawn_waits: Vec::new(), spawn_zombies: Vec::new(), seed: None }.save(level, state.zombies(), state.plants())?;
                         state.as_mut().set_page(Page::Level(LevelConfig::new(level, _self.surface, state,canvas)?));
                         Ok(())
                     }))